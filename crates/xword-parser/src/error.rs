@@ -8,8 +8,15 @@ pub enum ParseError {
     #[error("file too short: expected at least {expected} bytes, got {actual}")]
     FileTooShort { expected: usize, actual: usize },
 
-    #[error("checksum mismatch: expected {expected:#06x}, got {actual:#06x}")]
-    ChecksumMismatch { expected: u16, actual: u16 },
+    #[error("{region} checksum mismatch: expected {expected:#06x}, got {actual:#06x}")]
+    ChecksumMismatch {
+        region: String,
+        expected: u16,
+        actual: u16,
+    },
+
+    #[error("key does not unlock the scrambled solution")]
+    InvalidKey,
 
     #[error("invalid grid dimensions: {width}x{height}")]
     InvalidDimensions { width: u8, height: u8 },
@@ -0,0 +1,652 @@
+//! Parser and writer for the Exolve crossword format.
+//!
+//! Exolve puzzles are usually embedded in an HTML page; everything between
+//! the `exolve-begin` and `exolve-end` lines is read and the rest ignored.
+//!
+//! File format reference:
+//! https://github.com/viresh-ratnakar/exolve/blob/master/README.md
+
+use crate::error::ParseError;
+use crate::numbering::{direction_step, number_grid, Slot};
+use crate::puz::decode_string;
+use crate::text::single_line;
+use crate::types::{Bars, Cell, CellKind, Clue, ClueRef, Clues, Puzzle};
+
+const BEGIN: &str = "exolve-begin";
+const END: &str = "exolve-end";
+const SECTION_PREFIX: &str = "exolve-";
+
+/// Grid characters for a block and for a square with no known solution.
+const BLOCK: char = '.';
+const UNSOLVED: char = '0';
+
+/// Grid decorators that follow a cell character.
+const BAR_RIGHT: char = '|';
+const BAR_BOTTOM: char = '_';
+const BAR_BOTH: char = '+';
+const CIRCLE: char = '@';
+const PREFILLED: char = '!';
+const DECORATORS: &str = "|_+@!~*";
+
+/// An Exolve puzzle together with the ninas, which `Puzzle` does not model.
+#[derive(Debug, Clone)]
+pub struct Document {
+    pub puzzle: Puzzle,
+    /// Each nina as a list of (row, col) cells.
+    pub ninas: Vec<Vec<(usize, usize)>>,
+}
+
+impl From<Puzzle> for Document {
+    fn from(puzzle: Puzzle) -> Self {
+        Document {
+            puzzle,
+            ninas: Vec::new(),
+        }
+    }
+}
+
+/// A `exolve-<name>:` section with its inline value and following lines.
+struct Section<'a> {
+    name: &'a str,
+    value: &'a str,
+    lines: Vec<&'a str>,
+}
+
+/// Parse Exolve text (or an HTML page embedding it) into a `Puzzle`.
+pub fn parse(data: &[u8]) -> Result<Puzzle, ParseError> {
+    Ok(parse_document(data)?.puzzle)
+}
+
+/// Parse Exolve text, keeping the ninas.
+pub fn parse_document(data: &[u8]) -> Result<Document, ParseError> {
+    let text = decode_string(data);
+    let sections = split_sections(&text)?;
+    let section = |name: &str| sections.iter().find(|s| s.name == name);
+    let value = |name: &str| {
+        section(name)
+            .map(|s| s.value.to_string())
+            .unwrap_or_default()
+    };
+
+    let width = parse_dimension("width", &value("width"))?;
+    let height = parse_dimension("height", &value("height"))?;
+
+    let grid_lines = section("grid")
+        .map(|s| s.lines.as_slice())
+        .ok_or_else(|| ParseError::InvalidData("missing exolve-grid section".into()))?;
+    if grid_lines.len() != height as usize {
+        return Err(ParseError::InvalidData(format!(
+            "grid has {} rows, expected {}",
+            grid_lines.len(),
+            height
+        )));
+    }
+
+    let mut grid = Vec::with_capacity(height as usize);
+    for (row, line) in grid_lines.iter().enumerate() {
+        let cells = parse_grid_line(line)?;
+        if cells.len() != width as usize {
+            return Err(ParseError::InvalidData(format!(
+                "row {} has {} cells, expected {}",
+                row,
+                cells.len(),
+                width
+            )));
+        }
+        grid.push(cells);
+    }
+    let has_solution = grid
+        .iter()
+        .flatten()
+        .all(|cell| !matches!(cell.kind, CellKind::Letter) || cell.solution.is_some());
+
+    let (across_slots, down_slots) = number_grid(&mut grid);
+    let clue_lines = |name: &str| section(name).map(|s| s.lines.clone()).unwrap_or_default();
+    let across = parse_clues(&clue_lines("across"), &across_slots, "across")?;
+    let down = parse_clues(&clue_lines("down"), &down_slots, "down")?;
+
+    let ninas = sections
+        .iter()
+        .filter(|s| s.name == "nina")
+        .map(|s| parse_nina(s.value, width, height))
+        .filter(|nina| !nina.is_empty())
+        .collect();
+
+    let notes = section("preamble")
+        .map(|s| {
+            std::iter::once(s.value)
+                .chain(s.lines.iter().copied())
+                .collect::<Vec<_>>()
+                .join("\n")
+                .trim()
+                .to_string()
+        })
+        .unwrap_or_default();
+
+    Ok(Document {
+        puzzle: Puzzle {
+            title: value("title"),
+            author: value("setter"),
+            copyright: value("copyright"),
+            notes,
+            width,
+            height,
+            grid,
+            clues: Clues {
+                across,
+                down,
+                other: Vec::new(),
+            },
+            has_solution,
+            is_scrambled: false,
+            timer: None,
+        },
+        ninas,
+    })
+}
+
+/// Collect the `exolve-*` sections between `exolve-begin` and `exolve-end`.
+fn split_sections(text: &str) -> Result<Vec<Section<'_>>, ParseError> {
+    let mut lines = text.lines().map(str::trim);
+    if !lines.any(|line| line == BEGIN) {
+        return Err(ParseError::InvalidData("missing exolve-begin".into()));
+    }
+
+    let mut sections: Vec<Section> = Vec::new();
+    for line in lines {
+        if line == END {
+            return Ok(sections);
+        }
+        if let Some(rest) = line.strip_prefix(SECTION_PREFIX) {
+            let (name, value) = rest.split_once(':').unwrap_or((rest, ""));
+            sections.push(Section {
+                name: name.trim(),
+                value: value.trim(),
+                lines: Vec::new(),
+            });
+        } else if let Some(section) = sections.last_mut() {
+            if !line.is_empty() {
+                section.lines.push(line);
+            }
+        }
+    }
+    Err(ParseError::InvalidData("missing exolve-end".into()))
+}
+
+fn parse_dimension(name: &str, value: &str) -> Result<u8, ParseError> {
+    value
+        .parse::<u8>()
+        .ok()
+        .filter(|&n| n > 0)
+        .ok_or_else(|| ParseError::InvalidData(format!("invalid exolve-{}: {:?}", name, value)))
+}
+
+/// Parse one grid row. Cells may be separated by spaces, and each cell
+/// character may be followed by decorators such as `|` or `@`.
+fn parse_grid_line(line: &str) -> Result<Vec<Cell>, ParseError> {
+    let mut cells: Vec<Cell> = Vec::new();
+
+    for c in line.chars().filter(|c| !c.is_whitespace()) {
+        if DECORATORS.contains(c) {
+            let Some(cell) = cells.last_mut() else {
+                return Err(ParseError::InvalidData(format!(
+                    "decorator {:?} before any cell",
+                    c
+                )));
+            };
+            match c {
+                BAR_RIGHT => cell.bars.right = true,
+                BAR_BOTTOM => cell.bars.bottom = true,
+                BAR_BOTH => {
+                    cell.bars.right = true;
+                    cell.bars.bottom = true;
+                }
+                CIRCLE => cell.is_circled = true,
+                PREFILLED => {
+                    cell.is_given = cell.solution.is_some();
+                    cell.player_value = cell.solution.clone();
+                }
+                // Unnumbered and diagramless markers.
+                _ => {}
+            }
+            continue;
+        }
+
+        let cell = match c {
+            BLOCK => Cell::black(),
+            UNSOLVED | '?' => Cell::letter(),
+            c if c.is_alphanumeric() => Cell {
+                solution: Some(c.to_uppercase().to_string()),
+                ..Cell::letter()
+            },
+            c => {
+                return Err(ParseError::InvalidData(format!(
+                    "invalid grid character: {:?}",
+                    c
+                )))
+            }
+        };
+        cells.push(cell);
+    }
+
+    Ok(cells)
+}
+
+/// Parse clue lines such as "1 Feline friend (3)" or "2, 5 Linked (4,3)".
+/// The enumeration is taken out of the text, and the later numbers of a
+/// linked clue become the entries it continues into. Annotations after the
+/// enumeration are dropped.
+fn parse_clues(lines: &[&str], slots: &[Slot], direction: &str) -> Result<Vec<Clue>, ParseError> {
+    let mut clues = Vec::with_capacity(lines.len());
+    for line in lines {
+        let (number, continued, text) = split_clue_numbers(line, direction).ok_or_else(|| {
+            ParseError::InvalidData(format!("invalid {} clue: {}", direction, line))
+        })?;
+        let slot = slots.iter().find(|s| s.number == number).ok_or_else(|| {
+            ParseError::InvalidData(format!("no {} word numbered {}", direction, number))
+        })?;
+
+        let (text, enumeration) = match enumeration_span(text) {
+            Some((open, close)) => (&text[..open], Some(text[open + 1..close - 1].to_string())),
+            None => (text, None),
+        };
+        clues.push(Clue {
+            number,
+            text: text.trim().to_string(),
+            row: slot.row,
+            col: slot.col,
+            length: slot.length,
+            cells: slot.cells(direction_step(direction)),
+            label: None,
+            rich_text: Vec::new(),
+            enumeration,
+            references: Vec::new(),
+            continued,
+        });
+    }
+    Ok(clues)
+}
+
+/// Split the clue number and any linked numbers, as in "2, 5d" or "2, 5",
+/// from the rest of a clue line. Linked numbers are in `direction` unless
+/// suffixed with "a" or "d".
+fn split_clue_numbers<'a>(line: &'a str, direction: &str) -> Option<(u32, Vec<ClueRef>, &'a str)> {
+    let (number, mut rest) = leading_number(line)?;
+    let mut continued = Vec::new();
+    while let Some(after_comma) = rest.trim_start().strip_prefix(',') {
+        let (linked, after) = leading_number(after_comma.trim_start())?;
+        let letters = after
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(after.len());
+        let linked_direction = match after[..letters].to_lowercase().as_str() {
+            "" => direction,
+            "a" | "ac" | "across" => "across",
+            "d" | "dn" | "down" => "down",
+            _ => return None,
+        };
+        continued.push(ClueRef {
+            direction: direction_label(linked_direction).to_string(),
+            number: linked,
+        });
+        rest = &after[letters..];
+    }
+    Some((number, continued, rest.trim()))
+}
+
+/// The number at the start of `s` and the text after it.
+fn leading_number(s: &str) -> Option<(u32, &str)> {
+    let digits = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    Some((s[..digits].parse().ok()?, &s[digits..]))
+}
+
+/// The capitalized direction name used in clue references.
+fn direction_label(direction: &str) -> &'static str {
+    match direction {
+        "across" => "Across",
+        _ => "Down",
+    }
+}
+
+/// Byte offsets of the first enumeration such as "(5)" or "(3,4-2)",
+/// from its opening parenthesis to just past the closing one.
+fn enumeration_span(text: &str) -> Option<(usize, usize)> {
+    let mut search_from = 0;
+    while let Some(open) = text[search_from..].find('(').map(|i| i + search_from) {
+        let close = open + text[open..].find(')')?;
+        let inner = &text[open + 1..close];
+        let is_enumeration = inner.chars().any(|c| c.is_ascii_digit())
+            && inner
+                .chars()
+                .all(|c| c.is_ascii_digit() || ",-' .".contains(c));
+        if is_enumeration {
+            return Some((open, close + 1));
+        }
+        search_from = close + 1;
+    }
+    None
+}
+
+/// Parse an `exolve-nina` value such as "a1 b2 c3". Cells are named by
+/// column letter and row number counted from the bottom. Colour names and
+/// clue references are ignored.
+fn parse_nina(value: &str, width: u8, height: u8) -> Vec<(usize, usize)> {
+    value
+        .split_whitespace()
+        .filter_map(|token| {
+            let mut chars = token.chars();
+            let column = chars.next().filter(|c| c.is_ascii_lowercase())?;
+            let row_from_bottom: usize = chars.as_str().parse().ok()?;
+            let col = (column as u8 - b'a') as usize;
+            if col >= width as usize || row_from_bottom == 0 || row_from_bottom > height as usize {
+                return None;
+            }
+            Some((height as usize - row_from_bottom, col))
+        })
+        .collect()
+}
+
+/// Serialize a `Puzzle` to Exolve text.
+pub fn write(puzzle: &Puzzle) -> Result<Vec<u8>, ParseError> {
+    write_document(&Document::from(puzzle.clone()))
+}
+
+/// Serialize an Exolve document, including the ninas.
+///
+/// Exolve holds one character per square, so rebus squares are rejected.
+pub fn write_document(document: &Document) -> Result<Vec<u8>, ParseError> {
+    let puzzle = &document.puzzle;
+
+    let mut out = String::new();
+    let mut line = |indent: usize, text: &str| {
+        out.push_str(&"  ".repeat(indent));
+        out.push_str(text);
+        out.push('\n');
+    };
+
+    line(0, BEGIN);
+    line(1, &format!("exolve-width: {}", puzzle.width));
+    line(1, &format!("exolve-height: {}", puzzle.height));
+    for (name, value) in [
+        ("title", &puzzle.title),
+        ("setter", &puzzle.author),
+        ("copyright", &puzzle.copyright),
+    ] {
+        if !value.is_empty() {
+            line(1, &format!("exolve-{}: {}", name, single_line(value)));
+        }
+    }
+    if !puzzle.notes.is_empty() {
+        line(1, "exolve-preamble:");
+        for note in puzzle.notes.lines() {
+            line(2, note);
+        }
+    }
+
+    line(1, "exolve-grid:");
+    for (row, cells) in puzzle.grid.iter().enumerate() {
+        let mut tokens = Vec::with_capacity(cells.len());
+        for (col, cell) in cells.iter().enumerate() {
+            tokens.push(grid_token(cell, edge_bars(puzzle, row, col))?);
+        }
+        line(2, &tokens.join(" "));
+    }
+
+    let mut grid = puzzle.grid.clone();
+    let (across_slots, down_slots) = number_grid(&mut grid);
+    for (name, slots, clues) in [
+        ("across", &across_slots, &puzzle.clues.across),
+        ("down", &down_slots, &puzzle.clues.down),
+    ] {
+        line(1, &format!("exolve-{}:", name));
+        for slot in slots {
+            let clue = clues
+                .iter()
+                .find(|c| c.row == slot.row && c.col == slot.col);
+            line(2, &clue_line(slot.number, clue, name));
+        }
+    }
+
+    for nina in &document.ninas {
+        let cells: Vec<String> = nina
+            .iter()
+            .map(|&(row, col)| {
+                format!(
+                    "{}{}",
+                    (b'a' + col as u8) as char,
+                    puzzle.height as usize - row
+                )
+            })
+            .collect();
+        line(1, &format!("exolve-nina: {}", cells.join(" ")));
+    }
+    line(0, END);
+
+    Ok(out.into_bytes())
+}
+
+/// A clue line: the number with any linked numbers, the text and the
+/// enumeration.
+fn clue_line(number: u32, clue: Option<&Clue>, direction: &str) -> String {
+    let mut out = number.to_string();
+    let Some(clue) = clue else {
+        return out;
+    };
+    for linked in &clue.continued {
+        out.push_str(&format!(", {}", linked.number));
+        if !linked.direction.eq_ignore_ascii_case(direction) {
+            out.push_str(if linked.direction.eq_ignore_ascii_case("across") {
+                "a"
+            } else {
+                "d"
+            });
+        }
+    }
+    let text = single_line(&clue.text);
+    if !text.is_empty() {
+        out.push(' ');
+        out.push_str(&text);
+    }
+    if let Some(enumeration) = &clue.enumeration {
+        let enumeration = enumeration.trim_start_matches('(').trim_end_matches(')');
+        out.push_str(&format!(" ({})", enumeration));
+    }
+    out
+}
+
+/// The bars on the right and bottom edges of a cell, which are the only
+/// ones Exolve can express. Bars drawn on a neighbour's near side count too.
+fn edge_bars(puzzle: &Puzzle, row: usize, col: usize) -> Bars {
+    let bars = puzzle.grid[row][col].bars;
+    let neighbour = |r: usize, c: usize| {
+        puzzle
+            .grid
+            .get(r)
+            .and_then(|cells| cells.get(c))
+            .map(|cell| cell.bars)
+            .unwrap_or_default()
+    };
+    Bars {
+        right: bars.right || neighbour(row, col + 1).left,
+        bottom: bars.bottom || neighbour(row + 1, col).top,
+        ..Bars::default()
+    }
+}
+
+/// The grid token for one cell: its character followed by decorators.
+fn grid_token(cell: &Cell, bars: Bars) -> Result<String, ParseError> {
+    let mut token = String::new();
+    // Exolve has no void squares, so they are written as blocks
+    if !matches!(cell.kind, CellKind::Letter) {
+        token.push(BLOCK);
+    } else {
+        if cell.rebus_solution.is_some() {
+            return Err(ParseError::InvalidData(
+                "Exolve grids cannot hold rebus squares".into(),
+            ));
+        }
+        match cell.solution.as_deref().and_then(|s| s.chars().next()) {
+            Some(c) => token.extend(c.to_uppercase()),
+            None => token.push(UNSOLVED),
+        }
+        if cell.is_circled {
+            token.push(CIRCLE);
+        }
+        if cell.is_given {
+            token.push(PREFILLED);
+        }
+    }
+    match (bars.right, bars.bottom) {
+        (true, true) => token.push(BAR_BOTH),
+        (true, false) => token.push(BAR_RIGHT),
+        (false, true) => token.push(BAR_BOTTOM),
+        (false, false) => {}
+    }
+    Ok(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "<html><body><script>
+exolve-begin
+  exolve-width: 3
+  exolve-height: 3
+  exolve-title: Test Cryptic
+  exolve-setter: Test Setter
+  exolve-preamble:
+    First line
+    Second line
+  exolve-grid:
+    C A|T
+    O@. O
+    G I_N
+  exolve-across:
+    1 Feline friend (2) Annotation (not part of the clue)
+    3 Spirit (3)
+  exolve-down:
+    1 Tooth (3)
+    2 Weight (3)
+  exolve-nina: a3 c1
+exolve-end
+</script></body></html>";
+
+    #[test]
+    fn test_parse_sample() {
+        let document = parse_document(SAMPLE.as_bytes()).unwrap();
+        let puzzle = &document.puzzle;
+        assert_eq!(puzzle.title, "Test Cryptic");
+        assert_eq!(puzzle.author, "Test Setter");
+        assert_eq!(puzzle.notes, "First line\nSecond line");
+        assert_eq!((puzzle.width, puzzle.height), (3, 3));
+        assert!(puzzle.has_solution);
+
+        assert!(puzzle.grid[0][1].bars.right);
+        assert!(puzzle.grid[2][1].bars.bottom);
+        assert!(puzzle.grid[1][0].is_circled);
+        assert!(matches!(puzzle.grid[1][1].kind, CellKind::Black));
+        assert_eq!(document.ninas, vec![vec![(0, 0), (2, 2)]]);
+
+        // The bar after "A" ends 1 Across after two letters.
+        let across = &puzzle.clues.across;
+        assert_eq!(across[0].text, "Feline friend");
+        assert_eq!(across[0].enumeration.as_deref(), Some("2"));
+        assert_eq!(across[0].length, 2);
+        assert_eq!((across[1].number, across[1].row), (3, 2));
+        assert_eq!(puzzle.clues.down.len(), 2);
+        assert_eq!(puzzle.clues.down[1].col, 2);
+    }
+
+    #[test]
+    fn test_parse_unsolved_grid() {
+        let data = b"exolve-begin\nexolve-width: 2\nexolve-height: 1\nexolve-grid:\n00\nexolve-across:\n1 Clue (2)\nexolve-end\n";
+        let puzzle = parse(data).unwrap();
+        assert!(!puzzle.has_solution);
+        assert_eq!(puzzle.grid[0][0].solution, None);
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_input() {
+        assert!(parse(b"no puzzle here").is_err());
+        assert!(parse(b"exolve-begin\nexolve-width: 3\n").is_err());
+        let wrong_number = SAMPLE.replace("3 Spirit", "5 Spirit");
+        assert!(parse(wrong_number.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_enumeration_span() {
+        assert_eq!(enumeration_span("Clue (3,4) note"), Some((5, 10)));
+        assert_eq!(enumeration_span("Clue (see 2) more (5-2)"), Some((18, 23)));
+        assert_eq!(enumeration_span("No enumeration"), None);
+    }
+
+    #[test]
+    fn test_parse_linked_clues() {
+        let data = SAMPLE.replace("1 Feline friend (2) Annotation", "1, 2d Linked clue (2,3)");
+        let puzzle = parse(data.as_bytes()).unwrap();
+        let clue = &puzzle.clues.across[0];
+        assert_eq!(clue.number, 1);
+        assert_eq!(clue.text, "Linked clue");
+        assert_eq!(clue.enumeration.as_deref(), Some("2,3"));
+        assert_eq!(
+            clue.continued,
+            vec![ClueRef {
+                direction: "Down".into(),
+                number: 2
+            }]
+        );
+
+        let (number, continued, text) = split_clue_numbers("1, 3 Linked (2,2)", "down").unwrap();
+        assert_eq!((number, text), (1, "Linked (2,2)"));
+        assert_eq!(continued[0].direction, "Down");
+        assert_eq!(continued[0].number, 3);
+        assert!(split_clue_numbers("1, 3x Nope", "down").is_none());
+
+        let reparsed = parse(&write(&puzzle).unwrap()).unwrap();
+        assert_eq!(reparsed.clues.across[0].continued, clue.continued);
+        assert_eq!(reparsed.clues.across[0].text, "Linked clue");
+        assert_eq!(reparsed.clues.across[0].enumeration, clue.enumeration);
+    }
+
+    #[test]
+    fn test_write_round_trip() {
+        let document = parse_document(SAMPLE.as_bytes()).unwrap();
+        let written = write_document(&document).unwrap();
+        let reparsed = parse_document(&written).unwrap();
+
+        let bars = |p: &Puzzle| -> Vec<Bars> { p.grid.iter().flatten().map(|c| c.bars).collect() };
+        assert_eq!(bars(&reparsed.puzzle), bars(&document.puzzle));
+        assert_eq!(reparsed.ninas, document.ninas);
+        assert_eq!(reparsed.puzzle.notes, document.puzzle.notes);
+        assert!(reparsed.puzzle.grid[1][0].is_circled);
+        let texts = |p: &Puzzle| -> Vec<String> {
+            p.clues
+                .across
+                .iter()
+                .chain(&p.clues.down)
+                .map(|c| c.text.clone())
+                .collect()
+        };
+        assert_eq!(texts(&reparsed.puzzle), texts(&document.puzzle));
+    }
+
+    #[test]
+    fn test_prefilled_round_trip() {
+        let data = SAMPLE.replace("G I_N", "G! I_N");
+        let puzzle = parse(data.as_bytes()).unwrap();
+        assert!(puzzle.grid[2][0].is_given);
+        assert_eq!(puzzle.grid[2][0].player_value.as_deref(), Some("G"));
+        assert!(!puzzle.grid[2][1].is_given);
+
+        let reparsed = parse(&write(&puzzle).unwrap()).unwrap();
+        assert!(reparsed.grid[2][0].is_given);
+        assert!(!reparsed.grid[0][0].is_given);
+    }
+
+    #[test]
+    fn test_write_rejects_rebus() {
+        let mut puzzle = parse(SAMPLE.as_bytes()).unwrap();
+        puzzle.grid[0][0].rebus_solution = Some("CAT".into());
+        assert!(matches!(write(&puzzle), Err(ParseError::InvalidData(_))));
+    }
+}
@@ -0,0 +1,356 @@
+//! Parser for Guardian-style crossword JSON.
+//!
+//! There is no explicit grid: the grid is built from the position, direction
+//! and length of each entry, and every other square is a block.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::error::ParseError;
+use crate::markup::{self, strip_html_tags};
+use crate::numbering::run_cells;
+use crate::types::{Cell, CellKind, Clue, ClueRef, Clues, Puzzle, TextRun};
+
+/// Intermediate deserialization types for the crossword JSON.
+
+#[derive(Deserialize)]
+struct GuardianFile {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    creator: Option<GuardianCreator>,
+    #[serde(default)]
+    instructions: Option<String>,
+    dimensions: GuardianDimensions,
+    entries: Vec<GuardianEntry>,
+}
+
+#[derive(Deserialize)]
+struct GuardianCreator {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct GuardianDimensions {
+    cols: u8,
+    rows: u8,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GuardianEntry {
+    id: String,
+    number: u32,
+    #[serde(default)]
+    clue: String,
+    direction: String,
+    length: u8,
+    position: GuardianPosition,
+    #[serde(default)]
+    solution: Option<String>,
+    /// Ids of all entries in a linked clue, head first.
+    #[serde(default)]
+    group: Vec<String>,
+    /// Word separators keyed by character, at positions counted in letters.
+    #[serde(default)]
+    separator_locations: BTreeMap<String, Vec<usize>>,
+}
+
+#[derive(Deserialize)]
+struct GuardianPosition {
+    x: usize,
+    y: usize,
+}
+
+/// Parse Guardian-style crossword JSON into a `Puzzle`.
+///
+/// The head of a linked clue lists the entries it continues into, and each
+/// continuation refers back to the head and reads "See <head>" when it has no
+/// text. Clues whose text ends without an enumeration get one built from the
+/// word separators.
+pub fn parse(data: &[u8]) -> Result<Puzzle, ParseError> {
+    let file: GuardianFile = serde_json::from_slice(data)?;
+    let GuardianDimensions { cols, rows } = file.dimensions;
+    if cols == 0 || rows == 0 {
+        return Err(ParseError::InvalidDimensions {
+            width: cols,
+            height: rows,
+        });
+    }
+
+    let mut grid = vec![vec![Cell::black(); cols as usize]; rows as usize];
+    let mut has_solution = true;
+    let mut across = Vec::new();
+    let mut down = Vec::new();
+
+    for entry in &file.entries {
+        let (d_row, d_col) = match entry.direction.as_str() {
+            "across" => (0, 1),
+            "down" => (1, 0),
+            other => {
+                return Err(ParseError::InvalidData(format!(
+                    "unknown direction {:?} for {}",
+                    other, entry.id
+                )))
+            }
+        };
+
+        let solution: Vec<char> = entry
+            .solution
+            .as_deref()
+            .map(|s| s.to_uppercase().chars().collect())
+            .unwrap_or_default();
+        has_solution &= solution.len() == entry.length as usize;
+
+        for i in 0..entry.length as usize {
+            let (row, col) = (entry.position.y + i * d_row, entry.position.x + i * d_col);
+            let cell = grid
+                .get_mut(row)
+                .and_then(|r| r.get_mut(col))
+                .ok_or_else(|| {
+                    ParseError::InvalidData(format!("{} runs outside the grid", entry.id))
+                })?;
+            if matches!(cell.kind, CellKind::Black) {
+                *cell = Cell::letter();
+            }
+            if let Some(letter) = solution.get(i) {
+                cell.solution = Some(letter.to_string());
+            }
+            if i == 0 {
+                cell.number = Some(entry.number);
+            }
+        }
+
+        let (text, rich_text) = clue_text(entry, &file.entries);
+        let (references, continued) = group_refs(entry, &file.entries);
+        let enumeration = match trailing_enumeration(&text) {
+            Some(enumeration) => Some(enumeration.to_string()),
+            None if references.is_empty() => {
+                Some(enumeration(entry.length, &entry.separator_locations))
+            }
+            None => None,
+        };
+        let clue = Clue {
+            number: entry.number,
+            text,
+            row: entry.position.y,
+            col: entry.position.x,
+            length: entry.length,
+            cells: run_cells(
+                entry.position.y,
+                entry.position.x,
+                entry.length,
+                d_row,
+                d_col,
+            ),
+            label: None,
+            rich_text,
+            enumeration,
+            references,
+            continued,
+        };
+        if d_row == 0 {
+            across.push(clue);
+        } else {
+            down.push(clue);
+        }
+    }
+    across.sort_by_key(|c| c.number);
+    down.sort_by_key(|c| c.number);
+
+    Ok(Puzzle {
+        title: file.name,
+        author: file.creator.map(|c| c.name).unwrap_or_default(),
+        copyright: String::new(),
+        notes: file
+            .instructions
+            .as_deref()
+            .map(strip_html_tags)
+            .unwrap_or_default(),
+        width: cols,
+        height: rows,
+        grid,
+        clues: Clues {
+            across,
+            down,
+            other: Vec::new(),
+        },
+        has_solution,
+        is_scrambled: false,
+        timer: None,
+    })
+}
+
+/// The display text for an entry's clue, with its formatted runs.
+fn clue_text(entry: &GuardianEntry, entries: &[GuardianEntry]) -> (String, Vec<TextRun>) {
+    let (text, runs) = markup::clue_text(entry.clue.trim());
+
+    // An empty continuation of a linked clue points the solver at the head
+    if text.is_empty() {
+        if let Some(head) = group_head(entry, entries) {
+            return (format!("See {}", head.number), Vec::new());
+        }
+    }
+    (text, runs)
+}
+
+/// The head entry of the linked clue a continuation entry belongs to.
+fn group_head<'a>(
+    entry: &GuardianEntry,
+    entries: &'a [GuardianEntry],
+) -> Option<&'a GuardianEntry> {
+    let head = entry.group.first().filter(|head| **head != entry.id)?;
+    entries.iter().find(|e| e.id == *head)
+}
+
+/// The clue references for an entry's group: a continuation refers to its
+/// head, and the head continues into the rest of the group.
+fn group_refs(entry: &GuardianEntry, entries: &[GuardianEntry]) -> (Vec<ClueRef>, Vec<ClueRef>) {
+    if let Some(head) = group_head(entry, entries) {
+        return (vec![clue_ref(head)], Vec::new());
+    }
+    let continued = entry
+        .group
+        .iter()
+        .filter(|id| **id != entry.id)
+        .filter_map(|id| entries.iter().find(|e| e.id == *id))
+        .map(clue_ref)
+        .collect();
+    (Vec::new(), continued)
+}
+
+fn clue_ref(entry: &GuardianEntry) -> ClueRef {
+    let direction = match entry.direction.as_str() {
+        "across" => "Across",
+        _ => "Down",
+    };
+    ClueRef {
+        direction: direction.to_string(),
+        number: entry.number,
+    }
+}
+
+/// The enumeration at the end of a clue, such as "3,4" in "Clue (3,4)".
+fn trailing_enumeration(text: &str) -> Option<&str> {
+    let inner = text.strip_suffix(')')?;
+    let inner = &inner[inner.rfind('(')? + 1..];
+    let is_enumeration = inner.chars().any(|c| c.is_ascii_digit())
+        && inner
+            .chars()
+            .all(|c| c.is_ascii_digit() || ",-' .".contains(c));
+    is_enumeration.then_some(inner)
+}
+
+/// Build an enumeration such as "3,4" or "5-3" from word separators.
+fn enumeration(length: u8, separators: &BTreeMap<String, Vec<usize>>) -> String {
+    let mut breaks: Vec<(usize, &str)> = separators
+        .iter()
+        .flat_map(|(sep, positions)| positions.iter().map(move |&p| (p, sep.as_str())))
+        .filter(|&(p, _)| p > 0 && p < length as usize)
+        .collect();
+    breaks.sort();
+
+    let mut out = String::new();
+    let mut start = 0;
+    for (position, sep) in breaks {
+        out.push_str(&(position - start).to_string());
+        out.push_str(sep);
+        start = position;
+    }
+    out.push_str(&(length as usize - start).to_string());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"{
+        "id": "crosswords/cryptic/1",
+        "name": "Cryptic crossword No 1",
+        "creator": {"name": "Setter", "webUrl": "https://example.com"},
+        "dimensions": {"cols": 5, "rows": 3},
+        "crosswordType": "cryptic",
+        "entries": [
+            {"id": "1-across", "number": 1, "humanNumber": "1,4",
+             "clue": "Pet <i>and</i> its home (3,3)", "direction": "across",
+             "length": 3, "group": ["1-across", "4-across"],
+             "position": {"x": 0, "y": 0}, "separatorLocations": {",": [3]},
+             "solution": "CAT"},
+            {"id": "4-across", "number": 4, "humanNumber": "4",
+             "clue": "", "direction": "across", "length": 3,
+             "group": ["1-across", "4-across"], "position": {"x": 2, "y": 2},
+             "separatorLocations": {}, "solution": "DEN"},
+            {"id": "2-down", "number": 2, "humanNumber": "2",
+             "clue": "Cut short", "direction": "down", "length": 3,
+             "group": ["2-down"], "position": {"x": 2, "y": 0},
+             "separatorLocations": {"-": [1]}, "solution": "TED"}
+        ]
+    }"#;
+
+    #[test]
+    fn test_parse_sample() {
+        let puzzle = parse(SAMPLE.as_bytes()).unwrap();
+        assert_eq!(puzzle.title, "Cryptic crossword No 1");
+        assert_eq!(puzzle.author, "Setter");
+        assert_eq!((puzzle.width, puzzle.height), (5, 3));
+        assert!(puzzle.has_solution);
+
+        assert_eq!(puzzle.grid[0][0].number, Some(1));
+        assert_eq!(puzzle.grid[0][2].solution.as_deref(), Some("T"));
+        assert_eq!(puzzle.grid[2][2].solution.as_deref(), Some("D"));
+        assert!(matches!(puzzle.grid[1][0].kind, CellKind::Black));
+        assert!(matches!(puzzle.grid[0][4].kind, CellKind::Black));
+
+        let across = &puzzle.clues.across;
+        assert_eq!(across[0].text, "Pet and its home (3,3)");
+        assert!(across[0].rich_text[1].italic);
+        assert_eq!(across[0].rich_text[2].text, " its home (3,3)");
+        assert_eq!(across[0].enumeration.as_deref(), Some("3,3"));
+        assert_eq!(
+            across[0].continued,
+            vec![ClueRef {
+                direction: "Across".into(),
+                number: 4
+            }]
+        );
+        assert!(across[0].references.is_empty());
+        assert_eq!(across[1].text, "See 1");
+        assert_eq!(across[1].references[0].number, 1);
+        assert_eq!(across[1].enumeration, None);
+        assert_eq!((across[1].row, across[1].col, across[1].length), (2, 2, 3));
+        assert_eq!(puzzle.clues.down[0].text, "Cut short");
+        assert_eq!(puzzle.clues.down[0].enumeration.as_deref(), Some("1-2"));
+    }
+
+    #[test]
+    fn test_parse_without_solutions() {
+        let data = SAMPLE.replace(r#""solution": "TED""#, r#""solution": null"#);
+        let puzzle = parse(data.as_bytes()).unwrap();
+        assert!(!puzzle.has_solution);
+        // Crossing entries still fill the shared square.
+        assert_eq!(puzzle.grid[0][2].solution.as_deref(), Some("T"));
+        assert_eq!(puzzle.grid[1][2].solution, None);
+    }
+
+    #[test]
+    fn test_parse_rejects_entries_outside_grid() {
+        let data = SAMPLE.replace(r#""x": 2, "y": 2"#, r#""x": 4, "y": 2"#);
+        assert!(matches!(
+            parse(data.as_bytes()),
+            Err(ParseError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_enumeration() {
+        let mut separators = BTreeMap::new();
+        assert_eq!(enumeration(7, &separators), "7");
+        separators.insert(",".to_string(), vec![3]);
+        separators.insert("-".to_string(), vec![5]);
+        assert_eq!(enumeration(7, &separators), "3,2-2");
+        assert_eq!(trailing_enumeration("Clue (3,4)"), Some("3,4"));
+        assert_eq!(trailing_enumeration("Clue (see notes)"), None);
+    }
+}
@@ -1,8 +1,12 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::error::ParseError;
-use crate::types::{Cell, CellKind, Clue, Clues, Puzzle};
+use crate::markup::{clue_text, to_markup};
+use crate::numbering::{run_cells, word_length};
+use crate::types::{
+    parse_color, Bars, Cell, CellKind, Clue, ClueList, ClueRef, Clues, Puzzle, Timer,
+};
 
 /// Intermediate deserialization types for the ipuz JSON format.
 
@@ -13,6 +17,7 @@ struct IpuzFile {
     dimensions: Option<IpuzDimensions>,
     puzzle: Option<Vec<Vec<Value>>>,
     solution: Option<Vec<Vec<Value>>>,
+    saved: Option<Vec<Vec<Value>>>,
     clues: Option<IpuzClues>,
     #[serde(default)]
     title: Option<String>,
@@ -22,20 +27,28 @@ struct IpuzFile {
     copyright: Option<String>,
     #[serde(default)]
     notes: Option<String>,
+    /// ipuz has no timer field, so the solve timer is kept in an extension
+    /// field. The spec asks for extension names to be namespaced by a URL the
+    /// application owns, so other ipuz readers can ignore it safely.
+    #[serde(rename = "https://github.com/craffer/rebus#timer", default)]
+    timer: Option<Timer>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 struct IpuzDimensions {
     width: u8,
     height: u8,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 struct IpuzClues {
     #[serde(rename = "Across", default)]
     across: Vec<Value>,
     #[serde(rename = "Down", default)]
     down: Vec<Value>,
+    /// Lists in other directions, and keys with a label such as "Across:Horizontal".
+    #[serde(flatten)]
+    other: serde_json::Map<String, Value>,
 }
 
 /// Parse an ipuz (JSON) crossword file into a `Puzzle`.
@@ -69,6 +82,7 @@ pub fn parse(data: &[u8]) -> Result<Puzzle, ParseError> {
         .puzzle
         .ok_or_else(|| ParseError::InvalidData("missing puzzle grid".into()))?;
     let solution_grid = ipuz.solution.as_ref();
+    let saved_grid = ipuz.saved.as_ref();
 
     if puzzle_grid.len() != h {
         return Err(ParseError::InvalidData(format!(
@@ -92,19 +106,9 @@ pub fn parse(data: &[u8]) -> Result<Puzzle, ParseError> {
 
         let mut grid_row: Vec<Cell> = Vec::with_capacity(w);
         for (col, cell_val) in puzzle_row.iter().enumerate().take(w) {
-            let (is_black, cell_number, is_circled) = parse_puzzle_cell(cell_val);
-
-            if is_black {
-                grid_row.push(Cell {
-                    kind: CellKind::Black,
-                    number: None,
-                    solution: None,
-                    rebus_solution: None,
-                    player_value: None,
-                    is_circled: false,
-                    was_incorrect: false,
-                    is_revealed: false,
-                });
+            let cell = parse_puzzle_cell(cell_val);
+            if !matches!(cell.kind, CellKind::Letter) {
+                grid_row.push(cell);
                 continue;
             }
 
@@ -123,15 +127,19 @@ pub fn parse(data: &[u8]) -> Result<Puzzle, ParseError> {
                 (None, None)
             };
 
+            // A given letter wins over anything saved for the square
+            let player_value = cell.player_value.clone().or_else(|| {
+                saved_grid
+                    .and_then(|g| g.get(row))
+                    .and_then(|r| r.get(col))
+                    .and_then(parse_saved_cell)
+            });
+
             grid_row.push(Cell {
-                kind: CellKind::Letter,
-                number: cell_number,
                 solution,
                 rebus_solution,
-                player_value: None,
-                is_circled,
-                was_incorrect: false,
-                is_revealed: false,
+                player_value,
+                ..cell
             });
         }
         grid.push(grid_row);
@@ -142,8 +150,25 @@ pub fn parse(data: &[u8]) -> Result<Puzzle, ParseError> {
         .clues
         .ok_or_else(|| ParseError::InvalidData("missing clues".into()))?;
 
-    let across_clues = build_clues(&ipuz_clues.across, &grid, w, h, true)?;
-    let down_clues = build_clues(&ipuz_clues.down, &grid, w, h, false)?;
+    let mut across_clues = build_clues(&ipuz_clues.across, &grid, "Across")?;
+    let mut down_clues = build_clues(&ipuz_clues.down, &grid, "Down")?;
+    let mut other_clues = Vec::new();
+    for (key, values) in &ipuz_clues.other {
+        let Some(values) = values.as_array() else {
+            continue;
+        };
+        let (direction, label) = key.split_once(':').unwrap_or((key, key));
+        let clues = build_clues(values, &grid, direction)?;
+        match direction {
+            "Across" => across_clues.extend(clues),
+            "Down" => down_clues.extend(clues),
+            _ => other_clues.push(ClueList {
+                direction: direction.to_string(),
+                label: label.to_string(),
+                clues,
+            }),
+        }
+    }
 
     Ok(Puzzle {
         title: ipuz.title.unwrap_or_default(),
@@ -156,55 +181,97 @@ pub fn parse(data: &[u8]) -> Result<Puzzle, ParseError> {
         clues: Clues {
             across: across_clues,
             down: down_clues,
+            other: other_clues,
         },
         has_solution: solution_grid.is_some(),
         is_scrambled: false,
+        timer: ipuz.timer,
     })
 }
 
-/// Parse a cell value from the puzzle array.
-/// Returns (is_black, clue_number, is_circled).
-fn parse_puzzle_cell(val: &Value) -> (bool, Option<u32>, bool) {
+/// Parse a cell value from the puzzle array into a cell without answers,
+/// except for the letter of a given square.
+fn parse_puzzle_cell(val: &Value) -> Cell {
     match val {
         // "#" means black cell
-        Value::String(s) if s == "#" => (true, None, false),
-        // 0 means normal empty cell (no number)
-        Value::Number(n) if n.as_u64() == Some(0) => (false, None, false),
-        // Positive number means clue number
-        Value::Number(n) => {
-            let num = n.as_u64().unwrap_or(0) as u32;
-            if num > 0 {
-                (false, Some(num), false)
-            } else {
-                (false, None, false)
-            }
-        }
-        // null means omitted — treat as black
-        Value::Null => (true, None, false),
-        // Object with "cell" key and optional "style"
+        Value::String(s) if s == "#" => Cell::black(),
+        // 0 means normal empty cell (no number); positive number means clue number
+        Value::Number(n) => Cell {
+            number: n.as_u64().filter(|&n| n > 0).map(|n| n as u32),
+            ..Cell::letter()
+        },
+        // null means omitted: the square is not part of the grid
+        Value::Null => Cell::void(),
+        // Object with "cell" key, optional "style" and an optional given "value"
         Value::Object(obj) => {
-            let cell_num = obj.get("cell").and_then(|v| v.as_u64()).map(|n| n as u32);
-            let is_circled = obj
-                .get("style")
-                .and_then(|s| s.get("shapebg"))
-                .and_then(|v| v.as_str())
-                .map(|s| s == "circle")
-                .unwrap_or(false);
             // Check if this is a block
-            let is_block = obj
-                .get("cell")
-                .map(|v| v.as_str() == Some("#"))
-                .unwrap_or(false);
-            if is_block {
-                (true, None, false)
-            } else {
-                (false, cell_num.filter(|&n| n > 0), is_circled)
+            match obj.get("cell") {
+                Some(Value::String(s)) if s == "#" => return Cell::black(),
+                Some(Value::Null) => return Cell::void(),
+                _ => {}
+            }
+            let style = obj.get("style");
+            let style_str = |key: &str| style.and_then(|s| s.get(key)).and_then(|v| v.as_str());
+            Cell {
+                number: obj
+                    .get("cell")
+                    .and_then(|v| v.as_u64())
+                    .filter(|&n| n > 0)
+                    .map(|n| n as u32),
+                is_circled: style_str("shapebg") == Some("circle"),
+                bars: style_str("barred").map(parse_barred).unwrap_or_default(),
+                is_shaded: style
+                    .and_then(|s| s.get("highlight"))
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+                background_color: style_str("color").and_then(parse_color),
+                ..given_cell(obj.get("value"))
             }
         }
-        _ => (false, None, false),
+        _ => Cell::letter(),
+    }
+}
+
+/// A letter cell holding the given letter in an ipuz `value`, if there is one.
+fn given_cell(value: Option<&Value>) -> Cell {
+    match value
+        .and_then(|v| v.as_str())
+        .filter(|v| !v.is_empty() && *v != "#")
+    {
+        Some(letter) => Cell {
+            player_value: Some(letter.to_uppercase()),
+            is_given: true,
+            ..Cell::letter()
+        },
+        None => Cell::letter(),
     }
 }
 
+/// Parse a `barred` style such as "TL": one letter per barred edge.
+fn parse_barred(s: &str) -> Bars {
+    let has = |edge: char| s.chars().any(|c| c.eq_ignore_ascii_case(&edge));
+    Bars {
+        top: has('T'),
+        right: has('R'),
+        bottom: has('B'),
+        left: has('L'),
+    }
+}
+
+/// The `barred` style for a cell's bars, such as "TL".
+fn barred_style(bars: Bars) -> String {
+    [
+        (bars.top, 'T'),
+        (bars.right, 'R'),
+        (bars.bottom, 'B'),
+        (bars.left, 'L'),
+    ]
+    .iter()
+    .filter(|(set, _)| *set)
+    .map(|(_, edge)| *edge)
+    .collect()
+}
+
 /// Parse a solution cell value.
 /// Returns (solution, rebus_solution).
 fn parse_solution_cell(val: &Value) -> (Option<String>, Option<String>) {
@@ -239,52 +306,157 @@ fn parse_solution_cell(val: &Value) -> (Option<String>, Option<String>) {
     }
 }
 
-/// Build clue list from ipuz clue array.
-/// Each clue is either [number, "text"] or [number, "text", ...extra].
+/// Parse a cell value from the saved (player progress) array.
+fn parse_saved_cell(val: &Value) -> Option<String> {
+    let s = match val {
+        Value::String(s) => s.as_str(),
+        Value::Object(obj) => obj.get("value").and_then(|v| v.as_str())?,
+        _ => return None,
+    };
+    if s.is_empty() || s == "#" {
+        None
+    } else {
+        Some(s.to_uppercase())
+    }
+}
+
+/// Build a clue list in the given direction from an ipuz clue array.
+/// Each clue is either [number, "text"] or [number, "text", ...extra], or an
+/// object with `number`, `clue` and optionally the `cells` of its answer, an
+/// `enumeration`, and `references` and `continued` lists of other clues.
+/// Outside the across and down lists, the number may be a label such as "A".
 fn build_clues(
     clue_values: &[Value],
     grid: &[Vec<Cell>],
-    w: usize,
-    h: usize,
-    is_across: bool,
+    direction: &str,
 ) -> Result<Vec<Clue>, ParseError> {
+    let is_standard = matches!(direction, "Across" | "Down");
+    let step = clue_step(direction);
     let mut clues = Vec::new();
 
     for val in clue_values {
-        let (number, text) = match val {
-            Value::Array(arr) if arr.len() >= 2 => {
-                let num = arr[0]
-                    .as_u64()
-                    .ok_or_else(|| ParseError::InvalidData("clue number is not a number".into()))?
-                    as u32;
-                let text = arr[1].as_str().unwrap_or("").to_string();
-                (num, text)
-            }
+        let (number_val, text, obj) = match val {
+            Value::Array(arr) if arr.len() >= 2 => (&arr[0], arr[1].as_str(), None),
+            Value::Object(obj) => (
+                obj.get("number").unwrap_or(&Value::Null),
+                obj.get("clue").and_then(|v| v.as_str()),
+                Some(obj),
+            ),
             _ => continue, // Skip malformed clues
         };
+        let field = |key: &str| obj.and_then(|obj| obj.get(key));
+        let number = match number_val {
+            Value::Number(n) => n.as_u64().map(|n| n as u32),
+            Value::String(s) => s.parse().ok(),
+            _ => None,
+        };
+        let label = match number_val {
+            Value::String(s) if number.is_none() && !is_standard => Some(s.clone()),
+            _ => None,
+        };
+        if number.is_none() && label.is_none() {
+            return Err(ParseError::InvalidData(
+                "clue number is not a number".into(),
+            ));
+        }
+        let number = number.unwrap_or(0);
+        let (text, rich_text) = clue_text(text.unwrap_or(""));
 
-        // Find the grid position for this clue number
-        let (row, col) = find_clue_position(grid, number)
-            .ok_or_else(|| ParseError::InvalidData(format!("clue {} not found in grid", number)))?;
-
-        let length = if is_across {
-            compute_word_length_across(grid, w, row, col)
-        } else {
-            compute_word_length_down(grid, h, row, col)
+        let cells = match field("cells") {
+            Some(cells) => parse_clue_cells(cells, grid).ok_or_else(|| {
+                ParseError::InvalidData(format!("clue {} has invalid cells", number))
+            })?,
+            None => Vec::new(),
         };
 
+        // Without explicit cells, find the grid position for this clue number
+        let (row, col, length, cells) =
+            match (cells.first(), find_clue_position(grid, number), step) {
+                (Some(&(row, col)), _, _) => (row, col, cells.len() as u8, cells),
+                (None, Some((row, col)), Some((d_row, d_col))) => {
+                    let length = word_length(grid, row, col, d_row, d_col);
+                    (row, col, length, run_cells(row, col, length, d_row, d_col))
+                }
+                (None, Some((row, col)), None) => (row, col, 0, cells),
+                (None, None, _) if is_standard => {
+                    return Err(ParseError::InvalidData(format!(
+                        "clue {} not found in grid",
+                        number
+                    )))
+                }
+                (None, None, _) => (0, 0, 0, cells),
+            };
+
         clues.push(Clue {
             number,
             text,
             row,
             col,
             length,
+            cells,
+            label,
+            rich_text,
+            enumeration: field("enumeration").and_then(parse_enumeration),
+            references: parse_clue_refs(field("references")),
+            continued: parse_clue_refs(field("continued")),
         });
     }
 
     Ok(clues)
 }
 
+/// The (row, col) step between squares for an ipuz clue direction, if the
+/// answers in that direction are straight runs.
+fn clue_step(direction: &str) -> Option<(usize, usize)> {
+    match direction {
+        "Across" => Some((0, 1)),
+        "Down" => Some((1, 0)),
+        "Diagonal" => Some((1, 1)),
+        _ => None,
+    }
+}
+
+/// Parse a clue's `cells`: 1-based [column, row] pairs, all inside the grid.
+fn parse_clue_cells(val: &Value, grid: &[Vec<Cell>]) -> Option<Vec<(usize, usize)>> {
+    val.as_array()?
+        .iter()
+        .map(|pair| {
+            let col = pair.get(0)?.as_u64()?.checked_sub(1)? as usize;
+            let row = pair.get(1)?.as_u64()?.checked_sub(1)? as usize;
+            grid.get(row)?.get(col)?;
+            Some((row, col))
+        })
+        .collect()
+}
+
+/// An enumeration, which ipuz allows as a string ("3,4") or a bare number.
+fn parse_enumeration(val: &Value) -> Option<String> {
+    match val {
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Parse a list of `{"direction": ..., "number": ...}` clue references,
+/// skipping entries without a direction or a numeric clue number.
+fn parse_clue_refs(val: Option<&Value>) -> Vec<ClueRef> {
+    let Some(refs) = val.and_then(|v| v.as_array()) else {
+        return Vec::new();
+    };
+    refs.iter()
+        .filter_map(|r| {
+            let direction = r.get("direction")?.as_str()?.to_string();
+            let number = match r.get("number")? {
+                Value::Number(n) => n.as_u64()? as u32,
+                Value::String(s) => s.parse().ok()?,
+                _ => return None,
+            };
+            Some(ClueRef { direction, number })
+        })
+        .collect()
+}
+
 /// Find the grid position (row, col) of a cell with the given clue number.
 fn find_clue_position(grid: &[Vec<Cell>], number: u32) -> Option<(usize, usize)> {
     for (r, row) in grid.iter().enumerate() {
@@ -297,32 +469,194 @@ fn find_clue_position(grid: &[Vec<Cell>], number: u32) -> Option<(usize, usize)>
     None
 }
 
-/// Compute length of an across word starting at (row, col) using the Cell grid.
-fn compute_word_length_across(grid: &[Vec<Cell>], w: usize, row: usize, col: usize) -> u8 {
-    let mut length = 0u8;
-    let mut c = col;
-    while c < w {
-        if matches!(grid[row][c].kind, CellKind::Black) {
-            break;
-        }
-        length += 1;
-        c += 1;
-    }
-    length
+/// Top-level structure written by `write`.
+#[derive(Serialize)]
+struct IpuzOutput<'a> {
+    version: &'static str,
+    kind: [&'static str; 1],
+    dimensions: IpuzDimensions,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    title: &'a str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    author: &'a str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    copyright: &'a str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    notes: &'a str,
+    puzzle: Vec<Vec<Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    solution: Option<Vec<Vec<Value>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    saved: Option<Vec<Vec<Value>>>,
+    clues: IpuzClues,
+    /// Written under the same extension field as `IpuzFile::timer`.
+    #[serde(
+        rename = "https://github.com/craffer/rebus#timer",
+        skip_serializing_if = "Option::is_none"
+    )]
+    timer: Option<Timer>,
 }
 
-/// Compute length of a down word starting at (row, col) using the Cell grid.
-fn compute_word_length_down(grid: &[Vec<Cell>], h: usize, row: usize, col: usize) -> u8 {
-    let mut length = 0u8;
-    let mut r = row;
-    while r < h {
-        if matches!(grid[r][col].kind, CellKind::Black) {
-            break;
+/// Serialize a `Puzzle` to ipuz v2 JSON.
+///
+/// The solution grid is only written when the puzzle has one, and the saved
+/// grid only when the player has entered something.
+pub fn write(puzzle: &Puzzle) -> Result<Vec<u8>, ParseError> {
+    let puzzle_grid = map_grid(puzzle, |cell| match cell.kind {
+        CellKind::Black => Value::from("#"),
+        CellKind::Void => Value::Null,
+        CellKind::Letter => {
+            let number = Value::from(cell.number.unwrap_or(0));
+            let mut style = serde_json::Map::new();
+            if cell.is_circled {
+                style.insert("shapebg".into(), Value::from("circle"));
+            }
+            if !cell.bars.is_empty() {
+                style.insert("barred".into(), Value::from(barred_style(cell.bars)));
+            }
+            if cell.is_shaded {
+                style.insert("highlight".into(), Value::from(true));
+            }
+            if let Some(color) = &cell.background_color {
+                style.insert("color".into(), Value::from(color.trim_start_matches('#')));
+            }
+            let given = cell.player_value.as_deref().filter(|_| cell.is_given);
+            if style.is_empty() && given.is_none() {
+                return number;
+            }
+            let mut obj = serde_json::Map::new();
+            obj.insert("cell".into(), number);
+            if !style.is_empty() {
+                obj.insert("style".into(), Value::Object(style));
+            }
+            if let Some(given) = given {
+                obj.insert("value".into(), Value::from(given));
+            }
+            Value::Object(obj)
         }
-        length += 1;
-        r += 1;
+    });
+
+    let solution = puzzle.has_solution.then(|| {
+        map_grid(puzzle, |cell| match cell.kind {
+            CellKind::Black => Value::from("#"),
+            CellKind::Void => Value::Null,
+            CellKind::Letter => cell
+                .rebus_solution
+                .as_ref()
+                .or(cell.solution.as_ref())
+                .map(|s| Value::from(s.as_str()))
+                .unwrap_or(Value::Null),
+        })
+    });
+
+    let has_progress = puzzle
+        .grid
+        .iter()
+        .flatten()
+        .any(|cell| cell.player_value.is_some());
+    let saved = has_progress.then(|| {
+        map_grid(puzzle, |cell| match cell.kind {
+            CellKind::Black => Value::from("#"),
+            CellKind::Void => Value::Null,
+            CellKind::Letter => Value::from(cell.player_value.as_deref().unwrap_or("")),
+        })
+    });
+
+    let write_clues = |clues: &[Clue], direction: &str| -> Vec<Value> {
+        let step = clue_step(direction);
+        clues.iter().map(|c| write_clue(c, step)).collect()
+    };
+    let other = puzzle
+        .clues
+        .other
+        .iter()
+        .map(|list| {
+            let key = if list.label == list.direction {
+                list.direction.clone()
+            } else {
+                format!("{}:{}", list.direction, list.label)
+            };
+            (key, Value::from(write_clues(&list.clues, &list.direction)))
+        })
+        .collect();
+
+    let output = IpuzOutput {
+        version: "http://ipuz.org/v2",
+        kind: ["http://ipuz.org/crossword#1"],
+        dimensions: IpuzDimensions {
+            width: puzzle.width,
+            height: puzzle.height,
+        },
+        title: &puzzle.title,
+        author: &puzzle.author,
+        copyright: &puzzle.copyright,
+        notes: &puzzle.notes,
+        puzzle: puzzle_grid,
+        solution,
+        saved,
+        clues: IpuzClues {
+            across: write_clues(&puzzle.clues.across, "Across"),
+            down: write_clues(&puzzle.clues.down, "Down"),
+            other,
+        },
+        timer: puzzle.timer,
+    };
+
+    Ok(serde_json::to_vec_pretty(&output)?)
+}
+
+/// An ipuz clue: `[number, "text"]`, or an object when the clue has an
+/// enumeration or references, or its `cells` are not the straight run from
+/// the numbered square.
+fn write_clue(clue: &Clue, step: Option<(usize, usize)>) -> Value {
+    let number = match &clue.label {
+        Some(label) => Value::from(label.as_str()),
+        None => Value::from(clue.number),
+    };
+    let is_run = step.is_some_and(|(d_row, d_col)| {
+        clue.cells == run_cells(clue.row, clue.col, clue.length, d_row, d_col)
+    });
+    let is_plain =
+        clue.enumeration.is_none() && clue.references.is_empty() && clue.continued.is_empty();
+    let text = if clue.rich_text.is_empty() {
+        clue.text.clone()
+    } else {
+        to_markup(&clue.rich_text)
+    };
+    if is_plain && (clue.cells.is_empty() || is_run) {
+        return serde_json::json!([number, text]);
+    }
+
+    let mut obj = serde_json::Map::new();
+    obj.insert("number".into(), number);
+    obj.insert("clue".into(), Value::from(text));
+    if let Some(enumeration) = &clue.enumeration {
+        obj.insert("enumeration".into(), Value::from(enumeration.as_str()));
+    }
+    if !clue.cells.is_empty() && !is_run {
+        let cells: Vec<[usize; 2]> = clue
+            .cells
+            .iter()
+            .map(|&(row, col)| [col + 1, row + 1])
+            .collect();
+        obj.insert("cells".into(), serde_json::json!(cells));
     }
-    length
+    if !clue.references.is_empty() {
+        obj.insert("references".into(), serde_json::json!(clue.references));
+    }
+    if !clue.continued.is_empty() {
+        obj.insert("continued".into(), serde_json::json!(clue.continued));
+    }
+    Value::Object(obj)
+}
+
+/// Map every cell of the puzzle grid to a JSON value, preserving rows.
+fn map_grid(puzzle: &Puzzle, f: impl Fn(&Cell) -> Value) -> Vec<Vec<Value>> {
+    puzzle
+        .grid
+        .iter()
+        .map(|row| row.iter().map(&f).collect())
+        .collect()
 }
 
 #[cfg(test)]
@@ -413,6 +747,258 @@ mod tests {
         assert!(!puzzle.grid[0][1].is_circled);
     }
 
+    #[test]
+    fn test_void_cells_round_trip() {
+        let json = r##"{
+            "version": "http://ipuz.org/v2",
+            "kind": ["http://ipuz.org/crossword#1"],
+            "dimensions": { "width": 3, "height": 2 },
+            "puzzle": [[null, 1, {"cell": null}], [2, 0, "#"]],
+            "solution": [[null, "A", null], ["B", "C", "#"]],
+            "clues": {
+                "Across": [[2, "Second"]],
+                "Down": [[1, "First"]]
+            }
+        }"##;
+
+        let puzzle = parse(json.as_bytes()).unwrap();
+        assert!(matches!(puzzle.grid[0][0].kind, CellKind::Void));
+        assert!(matches!(puzzle.grid[0][2].kind, CellKind::Void));
+        assert!(matches!(puzzle.grid[1][2].kind, CellKind::Black));
+        assert_eq!(puzzle.clues.across[0].length, 2);
+
+        let written: Value = serde_json::from_slice(&write(&puzzle).unwrap()).unwrap();
+        assert_eq!(written["puzzle"][0], serde_json::json!([null, 1, null]));
+        assert_eq!(written["solution"][0][0], Value::Null);
+        let reparsed = parse(&write(&puzzle).unwrap()).unwrap();
+        assert!(matches!(reparsed.grid[0][0].kind, CellKind::Void));
+        assert!(matches!(reparsed.grid[1][2].kind, CellKind::Black));
+    }
+
+    #[test]
+    fn test_given_letters_round_trip() {
+        let json = r##"{
+            "version": "http://ipuz.org/v2",
+            "kind": ["http://ipuz.org/crossword#1"],
+            "dimensions": { "width": 3, "height": 1 },
+            "puzzle": [[1, {"cell": 0, "value": "b"}, 0]],
+            "solution": [["A", "B", "C"]],
+            "saved": [["X", "Y", ""]],
+            "clues": {
+                "Across": [[1, "Only"]],
+                "Down": []
+            }
+        }"##;
+
+        let puzzle = parse(json.as_bytes()).unwrap();
+        assert!(!puzzle.grid[0][0].is_given);
+        assert_eq!(puzzle.grid[0][0].player_value.as_deref(), Some("X"));
+        assert!(puzzle.grid[0][1].is_given);
+        assert_eq!(puzzle.grid[0][1].player_value.as_deref(), Some("B"));
+
+        let written: Value = serde_json::from_slice(&write(&puzzle).unwrap()).unwrap();
+        assert_eq!(
+            written["puzzle"][0][1],
+            serde_json::json!({"cell": 0, "value": "B"})
+        );
+        let reparsed = parse(&write(&puzzle).unwrap()).unwrap();
+        assert!(reparsed.grid[0][1].is_given);
+        assert!(!reparsed.grid[0][2].is_given);
+    }
+
+    #[test]
+    fn test_barred_grid_round_trip() {
+        let json = r##"{
+            "version": "http://ipuz.org/v2",
+            "kind": ["http://ipuz.org/crossword#1"],
+            "dimensions": { "width": 4, "height": 1 },
+            "puzzle": [
+                [1, 0, {"cell": 2, "style": {"barred": "L"}}, 0]
+            ],
+            "solution": [["A", "B", "C", "D"]],
+            "clues": {
+                "Across": [[1, "First"], [2, "Second"]],
+                "Down": []
+            }
+        }"##;
+
+        let puzzle = parse(json.as_bytes()).unwrap();
+        assert!(puzzle.grid[0][2].bars.left);
+        assert!(!puzzle.grid[0][2].bars.top);
+        assert_eq!(puzzle.clues.across[0].length, 2);
+        assert_eq!(puzzle.clues.across[1].length, 2);
+
+        let reparsed = parse(&write(&puzzle).unwrap()).unwrap();
+        assert_eq!(reparsed.grid[0][2].bars, puzzle.grid[0][2].bars);
+        assert_eq!(reparsed.clues.across[0].length, 2);
+    }
+
+    #[test]
+    fn test_named_clue_lists() {
+        let json = r##"{
+            "version": "http://ipuz.org/v2",
+            "kind": ["http://ipuz.org/crossword#1"],
+            "dimensions": { "width": 2, "height": 2 },
+            "puzzle": [[1, 2], [3, 0]],
+            "solution": [["A", "B"], ["C", "D"]],
+            "clues": {
+                "Across:Horizontal": [[1, "First"], [3, "Second"]],
+                "Down": [[1, "Third"], [2, "Fourth"]],
+                "Diagonal": [[1, "Slant"]],
+                "Rows": [["A", "Top row"]]
+            }
+        }"##;
+
+        let puzzle = parse(json.as_bytes()).unwrap();
+        assert_eq!(puzzle.clues.across.len(), 2);
+        assert_eq!(puzzle.clues.down.len(), 2);
+
+        let other = &puzzle.clues.other;
+        assert_eq!(other.len(), 2);
+        assert_eq!(other[0].direction, "Diagonal");
+        assert_eq!(other[0].clues[0].length, 2);
+        assert_eq!(other[1].label, "Rows");
+        assert_eq!(other[1].clues[0].label.as_deref(), Some("A"));
+        assert_eq!(other[1].clues[0].length, 0);
+
+        let reparsed = parse(&write(&puzzle).unwrap()).unwrap();
+        assert_eq!(reparsed.clues.other.len(), 2);
+        assert_eq!(reparsed.clues.other[1].clues[0].text, "Top row");
+        assert_eq!(reparsed.clues.other[1].clues[0].label.as_deref(), Some("A"));
+    }
+
+    #[test]
+    fn test_clue_cells_round_trip() {
+        let json = r##"{
+            "version": "http://ipuz.org/v2",
+            "kind": ["http://ipuz.org/crossword#1"],
+            "dimensions": { "width": 2, "height": 2 },
+            "puzzle": [[1, 2], [3, 0]],
+            "solution": [["A", "B"], ["C", "D"]],
+            "clues": {
+                "Across": [[1, "First"], [3, "Second"]],
+                "Down": [[1, "Third"], [2, "Fourth"]],
+                "Clues": [{"number": 1, "clue": "Around", "cells": [[1, 1], [2, 1], [2, 2], [1, 2]]}]
+            }
+        }"##;
+
+        let puzzle = parse(json.as_bytes()).unwrap();
+        assert_eq!(puzzle.clues.across[1].cells, vec![(1, 0), (1, 1)]);
+        assert_eq!(puzzle.clues.down[1].cells, vec![(0, 1), (1, 1)]);
+        let around = &puzzle.clues.other[0].clues[0];
+        assert_eq!(around.text, "Around");
+        assert_eq!(around.cells, vec![(0, 0), (0, 1), (1, 1), (1, 0)]);
+        assert_eq!(around.length, 4);
+
+        let reparsed = parse(&write(&puzzle).unwrap()).unwrap();
+        assert_eq!(reparsed.clues.other[0].clues[0].cells, around.cells);
+        assert_eq!(reparsed.clues.across[1].cells, vec![(1, 0), (1, 1)]);
+
+        let outside = json.replace("[1, 2]]}", "[1, 3]]}");
+        assert!(matches!(
+            parse(outside.as_bytes()),
+            Err(ParseError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_clue_objects_round_trip() {
+        let json = r##"{
+            "version": "http://ipuz.org/v2",
+            "kind": ["http://ipuz.org/crossword#1"],
+            "dimensions": { "width": 2, "height": 2 },
+            "puzzle": [[1, 2], [3, 0]],
+            "solution": [["A", "B"], ["C", "D"]],
+            "clues": {
+                "Across": [
+                    {"number": 1, "clue": "First", "enumeration": "1-1",
+                     "continued": [{"direction": "Down", "number": 2}]},
+                    {"number": "3", "clue": "Second", "enumeration": 2}
+                ],
+                "Down": [
+                    [1, "Third"],
+                    {"number": 2, "clue": "See 1",
+                     "references": [{"direction": "Across", "number": "1"}]}
+                ]
+            }
+        }"##;
+
+        let puzzle = parse(json.as_bytes()).unwrap();
+        let across = &puzzle.clues.across;
+        assert_eq!(across[0].enumeration.as_deref(), Some("1-1"));
+        assert_eq!(
+            across[0].continued,
+            vec![ClueRef {
+                direction: "Down".into(),
+                number: 2
+            }]
+        );
+        assert_eq!(across[1].number, 3);
+        assert_eq!(across[1].enumeration.as_deref(), Some("2"));
+        assert_eq!(across[1].cells, vec![(1, 0), (1, 1)]);
+        assert_eq!(puzzle.clues.down[0].enumeration, None);
+        assert_eq!(puzzle.clues.down[1].references[0].number, 1);
+
+        let reparsed = parse(&write(&puzzle).unwrap()).unwrap();
+        assert_eq!(reparsed.clues.across[0].enumeration.as_deref(), Some("1-1"));
+        assert_eq!(reparsed.clues.across[0].continued, across[0].continued);
+        assert_eq!(
+            reparsed.clues.down[1].references,
+            puzzle.clues.down[1].references
+        );
+        assert_eq!(reparsed.clues.across[1].cells, across[1].cells);
+    }
+
+    #[test]
+    fn test_formatted_clue_round_trip() {
+        let json = String::from_utf8(make_test_ipuz())
+            .unwrap()
+            .replace(r#""A feline""#, r#""<i>Feline</i> H<sup>2</sup> pet""#);
+        let puzzle = parse(json.as_bytes()).unwrap();
+        let clue = &puzzle.clues.across[0];
+        assert_eq!(clue.text, "Feline H2 pet");
+        assert!(clue.rich_text[0].italic);
+        assert!(clue.rich_text[2].superscript);
+
+        let reparsed = parse(&write(&puzzle).unwrap()).unwrap();
+        assert_eq!(reparsed.clues.across[0].rich_text, clue.rich_text);
+        assert_eq!(reparsed.clues.across[0].text, clue.text);
+    }
+
+    #[test]
+    fn test_shaded_and_colored_round_trip() {
+        let json = r##"{
+            "version": "http://ipuz.org/v2",
+            "kind": ["http://ipuz.org/crossword#1"],
+            "dimensions": { "width": 3, "height": 1 },
+            "puzzle": [
+                [{"cell": 1, "style": {"highlight": true}}, {"cell": 0, "style": {"color": "ff8000"}}, 0]
+            ],
+            "solution": [["A", "B", "C"]],
+            "clues": {
+                "Across": [[1, "Test"]],
+                "Down": []
+            }
+        }"##;
+
+        let puzzle = parse(json.as_bytes()).unwrap();
+        assert!(puzzle.grid[0][0].is_shaded);
+        assert_eq!(puzzle.grid[0][0].number, Some(1));
+        assert_eq!(
+            puzzle.grid[0][1].background_color.as_deref(),
+            Some("#FF8000")
+        );
+        assert!(!puzzle.grid[0][2].is_shaded);
+        assert_eq!(puzzle.grid[0][2].background_color, None);
+
+        let reparsed = parse(&write(&puzzle).unwrap()).unwrap();
+        assert!(reparsed.grid[0][0].is_shaded);
+        assert_eq!(
+            reparsed.grid[0][1].background_color.as_deref(),
+            Some("#FF8000")
+        );
+    }
+
     #[test]
     fn test_parse_ipuz_rebus() {
         let json = r##"{
@@ -453,4 +1039,85 @@ mod tests {
         let err = parse(b"not json").unwrap_err();
         assert!(matches!(err, ParseError::Json(_)));
     }
+
+    #[test]
+    fn test_parse_ipuz_saved_grid() {
+        let json = r##"{
+            "version": "http://ipuz.org/v2",
+            "kind": ["http://ipuz.org/crossword#1"],
+            "dimensions": { "width": 3, "height": 1 },
+            "puzzle": [[1, 0, 0]],
+            "solution": [["A", "B", "C"]],
+            "saved": [["a", "", {"value": "heart"}]],
+            "clues": {
+                "Across": [[1, "Test"]],
+                "Down": []
+            }
+        }"##;
+
+        let puzzle = parse(json.as_bytes()).unwrap();
+        assert_eq!(puzzle.grid[0][0].player_value, Some("A".to_string()));
+        assert!(puzzle.grid[0][1].player_value.is_none());
+        assert_eq!(puzzle.grid[0][2].player_value, Some("HEART".to_string()));
+    }
+
+    #[test]
+    fn test_write_round_trip() {
+        let mut original = parse(&make_test_ipuz()).unwrap();
+        original.grid[0][0].rebus_solution = Some("CAT".to_string());
+        original.grid[0][1].is_circled = true;
+        original.grid[2][0].player_value = Some("D".to_string());
+
+        let written = write(&original).unwrap();
+        let json: Value = serde_json::from_slice(&written).unwrap();
+        assert_eq!(json["kind"][0], "http://ipuz.org/crossword#1");
+        assert_eq!(json["puzzle"][1][0], "#");
+        assert_eq!(json["solution"][0][0], "CAT");
+        assert_eq!(json["saved"][2][0], "D");
+
+        let puzzle = parse(&written).unwrap();
+        assert_eq!(puzzle.title, "Test Puzzle");
+        assert_eq!(puzzle.author, "Test Author");
+        assert_eq!(puzzle.grid[0][0].number, Some(1));
+        assert_eq!(puzzle.grid[0][0].rebus_solution, Some("CAT".to_string()));
+        assert!(puzzle.grid[0][1].is_circled);
+        assert_eq!(puzzle.grid[2][0].player_value, Some("D".to_string()));
+        assert!(puzzle.grid[1][1].player_value.is_none());
+        assert!(matches!(puzzle.grid[1][2].kind, CellKind::Black));
+        assert_eq!(puzzle.clues.across.len(), 2);
+        assert_eq!(puzzle.clues.down.len(), 3);
+        assert_eq!(puzzle.clues.down[2].text, "Several of these");
+        assert_eq!(puzzle.clues.down[2].length, 1);
+    }
+
+    const TIMER_FIELD: &str = "https://github.com/craffer/rebus#timer";
+
+    #[test]
+    fn test_timer_round_trip() {
+        let mut original = parse(&make_test_ipuz()).unwrap();
+        assert!(original.timer.is_none());
+        original.timer = Some(Timer {
+            elapsed_seconds: 95,
+            is_running: true,
+        });
+
+        let written = write(&original).unwrap();
+        let json: Value = serde_json::from_slice(&written).unwrap();
+        assert_eq!(json[TIMER_FIELD]["elapsed_seconds"], 95);
+
+        let puzzle = parse(&written).unwrap();
+        assert_eq!(puzzle.timer, original.timer);
+    }
+
+    #[test]
+    fn test_write_omits_missing_solution_and_progress() {
+        let mut original = parse(&make_test_ipuz()).unwrap();
+        original.has_solution = false;
+
+        let json: Value = serde_json::from_slice(&write(&original).unwrap()).unwrap();
+        assert!(json.get("solution").is_none());
+        assert!(json.get("saved").is_none());
+        assert!(json.get("notes").is_none());
+        assert!(json.get(TIMER_FIELD).is_none());
+    }
 }
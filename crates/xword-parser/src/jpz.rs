@@ -1,15 +1,25 @@
 use std::collections::HashMap;
-use std::io::{Cursor, Read};
+use std::io::{Cursor, Read, Write};
 
-use quick_xml::events::Event;
-use quick_xml::Reader;
+use quick_xml::events::{BytesDecl, BytesText, Event};
+use quick_xml::{Reader, Writer};
 
 use crate::error::ParseError;
-use crate::types::{Cell, CellKind, Clue, Clues, Puzzle};
+use crate::markup::{clue_text, to_markup};
+use crate::numbering::run_cells;
+use crate::types::{parse_color, Bars, Cell, CellKind, Clue, ClueList, Clues, Puzzle, Timer};
 
 /// ZIP magic bytes (PK\x03\x04).
 const ZIP_MAGIC: &[u8] = &[0x50, 0x4B, 0x03, 0x04];
 
+/// Namespaces written on the root and puzzle elements.
+const CROSSWORD_COMPILER_NS: &str = "http://crossword.info/xml/crossword-compiler";
+const RECTANGULAR_PUZZLE_NS: &str = "http://crossword.info/xml/rectangular-puzzle";
+
+/// Background color written for shaded squares that have no color of their
+/// own. Read back, it also marks the square as shaded.
+const SHADED_COLOR: &str = "#DCDCDC";
+
 /// Parse a JPZ or Crossword Compiler XML file into a `Puzzle`.
 ///
 /// JPZ files are ZIP archives containing an XML file. If the data starts with
@@ -46,13 +56,284 @@ fn extract_from_zip(data: &[u8]) -> Result<Vec<u8>, ParseError> {
     Ok(contents)
 }
 
+/// Serialize a `Puzzle` to Crossword Compiler XML.
+///
+/// When `zipped` is true, the XML is wrapped in a single-entry ZIP archive,
+/// the container used by .jpz files.
+pub fn write(puzzle: &Puzzle, zipped: bool) -> Result<Vec<u8>, ParseError> {
+    let xml = write_xml(puzzle).map_err(|e| ParseError::Xml(format!("XML write error: {}", e)))?;
+
+    if zipped {
+        write_zip(&xml)
+    } else {
+        Ok(xml)
+    }
+}
+
+/// Wrap XML data in a ZIP archive with a single deflated entry.
+fn write_zip(xml: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let mut archive = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    archive
+        .start_file("puzzle.xml", options)
+        .map_err(|e| ParseError::Xml(format!("ZIP error: {}", e)))?;
+    archive
+        .write_all(xml)
+        .map_err(|e| ParseError::Xml(format!("ZIP compress error: {}", e)))?;
+    let cursor = archive
+        .finish()
+        .map_err(|e| ParseError::Xml(format!("ZIP error: {}", e)))?;
+
+    Ok(cursor.into_inner())
+}
+
+/// Write the `<crossword-compiler-applet>` document for a puzzle.
+fn write_xml(puzzle: &Puzzle) -> std::io::Result<Vec<u8>> {
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+
+    writer
+        .create_element("crossword-compiler-applet")
+        .with_attribute(("xmlns", CROSSWORD_COMPILER_NS))
+        .write_inner_content(|w| {
+            if let Some(timer) = puzzle.timer {
+                write_applet_settings(w, timer)?;
+            }
+            w.create_element("rectangular-puzzle")
+                .with_attribute(("xmlns", RECTANGULAR_PUZZLE_NS))
+                .with_attribute(("alphabet", "ABCDEFGHIJKLMNOPQRSTUVWXYZ"))
+                .write_inner_content(|w| {
+                    write_metadata(w, puzzle)?;
+                    w.create_element("crossword").write_inner_content(|w| {
+                        write_grid(w, puzzle)?;
+                        write_words_and_clues(w, puzzle)
+                    })?;
+                    Ok(())
+                })?;
+            Ok(())
+        })?;
+
+    Ok(writer.into_inner())
+}
+
+/// Write the `<applet-settings>` element carrying the saved timer.
+fn write_applet_settings(w: &mut Writer<Vec<u8>>, timer: Timer) -> std::io::Result<()> {
+    let elapsed = timer.elapsed_seconds.to_string();
+    w.create_element("applet-settings")
+        .write_inner_content(|w| {
+            w.create_element("timer")
+                .with_attribute(("initial-value", elapsed.as_str()))
+                .with_attribute((
+                    "start-on-load",
+                    if timer.is_running { "true" } else { "false" },
+                ))
+                .write_empty()?;
+            Ok(())
+        })?;
+    Ok(())
+}
+
+/// Write the `<metadata>` element.
+fn write_metadata(w: &mut Writer<Vec<u8>>, puzzle: &Puzzle) -> std::io::Result<()> {
+    w.create_element("metadata").write_inner_content(|w| {
+        for (name, value) in [
+            ("title", &puzzle.title),
+            ("creator", &puzzle.author),
+            ("copyright", &puzzle.copyright),
+            ("description", &puzzle.notes),
+        ] {
+            w.create_element(name)
+                .write_text_content(BytesText::new(value))?;
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
+
+/// Write the `<grid>` element with one `<cell>` per square.
+fn write_grid(w: &mut Writer<Vec<u8>>, puzzle: &Puzzle) -> std::io::Result<()> {
+    let width = puzzle.width.to_string();
+    let height = puzzle.height.to_string();
+
+    w.create_element("grid")
+        .with_attribute(("width", width.as_str()))
+        .with_attribute(("height", height.as_str()))
+        .write_inner_content(|w| {
+            // Cells are written column by column, as Crossword Compiler does
+            for col in 0..puzzle.width as usize {
+                for row in 0..puzzle.height as usize {
+                    let Some(cell) = puzzle.grid.get(row).and_then(|r| r.get(col)) else {
+                        continue;
+                    };
+                    let mut attrs =
+                        vec![("x", (col + 1).to_string()), ("y", (row + 1).to_string())];
+                    match cell.kind {
+                        CellKind::Black => attrs.push(("type", "block".to_string())),
+                        CellKind::Void => attrs.push(("type", "void".to_string())),
+                        CellKind::Letter => {
+                            if let Some(sol) =
+                                cell.rebus_solution.as_ref().or(cell.solution.as_ref())
+                            {
+                                attrs.push(("solution", sol.clone()));
+                            }
+                            if let Some(number) = cell.number {
+                                attrs.push(("number", number.to_string()));
+                            }
+                            if cell.is_given {
+                                attrs.push(("hint", "true".to_string()));
+                            }
+                            if cell.is_circled {
+                                attrs.push(("background-shape", "circle".to_string()));
+                            }
+                            if let Some(color) = cell
+                                .background_color
+                                .as_deref()
+                                .or(cell.is_shaded.then_some(SHADED_COLOR))
+                            {
+                                attrs.push(("background-color", color.to_string()));
+                            }
+                        }
+                    }
+                    // jpz only has top and left bars, so a bar drawn on the
+                    // far side of a neighbour is moved onto this cell.
+                    let above = row
+                        .checked_sub(1)
+                        .and_then(|r| puzzle.grid.get(r))
+                        .and_then(|r| r.get(col));
+                    let before = col.checked_sub(1).and_then(|c| puzzle.grid[row].get(c));
+                    if cell.bars.top || above.is_some_and(|c| c.bars.bottom) {
+                        attrs.push(("top-bar", "true".to_string()));
+                    }
+                    if cell.bars.left || before.is_some_and(|c| c.bars.right) {
+                        attrs.push(("left-bar", "true".to_string()));
+                    }
+                    w.create_element("cell")
+                        .with_attributes(attrs.iter().map(|(k, v)| (*k, v.as_str())))
+                        .write_empty()?;
+                }
+            }
+            Ok(())
+        })?;
+    Ok(())
+}
+
+/// The clues of one `<clues>` list, each with the id of its `<word>` if it has one.
+type ClueEntries<'a> = Vec<(Option<String>, &'a Clue)>;
+
+/// Write a `<word>` for every clue with known squares, followed by the `<clues>` lists.
+///
+/// Across and down answers are written as `x`/`y` spans; any other answer
+/// lists its squares in `<cells>` children.
+fn write_words_and_clues(w: &mut Writer<Vec<u8>>, puzzle: &Puzzle) -> std::io::Result<()> {
+    let span = |start: usize, length: u8| {
+        if length > 1 {
+            format!("{}-{}", start + 1, start + length as usize)
+        } else {
+            (start + 1).to_string()
+        }
+    };
+
+    let mut word_id = 0;
+    let mut lists: Vec<(&str, ClueEntries)> = Vec::new();
+    let standard = [
+        ("Across", &puzzle.clues.across, Some((0, 1))),
+        ("Down", &puzzle.clues.down, Some((1, 0))),
+    ];
+    let other = puzzle
+        .clues
+        .other
+        .iter()
+        .map(|list| (list.label.as_str(), &list.clues, None));
+    for (title, clues, step) in standard.into_iter().chain(other) {
+        let mut entries = Vec::with_capacity(clues.len());
+        for clue in clues {
+            let run = step.filter(|&(d_row, d_col)| {
+                clue.cells.is_empty()
+                    || clue.cells == run_cells(clue.row, clue.col, clue.length, d_row, d_col)
+            });
+            if run.is_none() && clue.cells.is_empty() {
+                entries.push((None, clue));
+                continue;
+            }
+            word_id += 1;
+            let id = word_id.to_string();
+            let word = w.create_element("word").with_attribute(("id", id.as_str()));
+            match run {
+                // An across run stays on its row
+                Some((0, _)) => {
+                    let (x, y) = (span(clue.col, clue.length), (clue.row + 1).to_string());
+                    word.with_attribute(("x", x.as_str()))
+                        .with_attribute(("y", y.as_str()))
+                        .write_empty()?;
+                }
+                Some(_) => {
+                    let (x, y) = ((clue.col + 1).to_string(), span(clue.row, clue.length));
+                    word.with_attribute(("x", x.as_str()))
+                        .with_attribute(("y", y.as_str()))
+                        .write_empty()?;
+                }
+                None => {
+                    word.write_inner_content(|w| {
+                        for &(row, col) in &clue.cells {
+                            let (x, y) = ((col + 1).to_string(), (row + 1).to_string());
+                            w.create_element("cells")
+                                .with_attribute(("x", x.as_str()))
+                                .with_attribute(("y", y.as_str()))
+                                .write_empty()?;
+                        }
+                        Ok(())
+                    })?;
+                }
+            }
+            entries.push((Some(id), clue));
+        }
+        lists.push((title, entries));
+    }
+
+    for (title, entries) in lists {
+        w.create_element("clues")
+            .with_attribute(("ordering", "normal"))
+            .write_inner_content(|w| {
+                w.create_element("title").write_inner_content(|w| {
+                    w.create_element("b")
+                        .write_text_content(BytesText::new(title))?;
+                    Ok(())
+                })?;
+                for (id, clue) in &entries {
+                    let number = clue
+                        .label
+                        .clone()
+                        .unwrap_or_else(|| clue.number.to_string());
+                    let mut element = w.create_element("clue");
+                    if let Some(id) = id {
+                        element = element.with_attribute(("word", id.as_str()));
+                    }
+                    element = element.with_attribute(("number", number.as_str()));
+                    if let Some(format) = &clue.enumeration {
+                        element = element.with_attribute(("format", format.as_str()));
+                    }
+                    // Formatting is written as <b>, <i>, ... elements inside the
+                    // clue, unindented so no spaces creep into the text
+                    if clue.rich_text.is_empty() {
+                        element.write_text_content(BytesText::new(&clue.text))?;
+                    } else {
+                        let markup = to_markup(&clue.rich_text);
+                        element.write_text_content(BytesText::from_escaped(markup))?;
+                    }
+                }
+                Ok(())
+            })?;
+    }
+    Ok(())
+}
+
 /// A word definition from <word> elements.
 #[derive(Debug, Clone)]
 struct WordDef {
     id: String,
-    start_col: usize, // 0-indexed
-    start_row: usize, // 0-indexed
-    length: u8,
+    cells: Vec<(usize, usize)>, // 0-indexed (row, col), in answer order
 }
 
 /// A parsed cell from <cell> elements.
@@ -63,7 +344,12 @@ struct RawCell {
     solution: Option<String>,
     number: Option<u32>,
     is_block: bool,
+    is_void: bool,
+    /// A `hint="true"` square, whose solution is shown from the start.
+    is_given: bool,
     is_circled: bool,
+    bars: Bars,
+    background_color: Option<String>,
 }
 
 /// A parsed clue from <clue> elements.
@@ -71,6 +357,11 @@ struct RawCell {
 struct RawClue {
     word_id: String,
     number: u32,
+    /// The `number` attribute when it is not numeric, such as "A".
+    label: Option<String>,
+    /// The `format` attribute, an enumeration such as "3,4".
+    format: Option<String>,
+    /// The clue text, with any formatting as HTML-style markup.
     text: String,
 }
 
@@ -88,20 +379,23 @@ fn parse_xml(data: &[u8]) -> Result<Puzzle, ParseError> {
     let mut grid_height: u8 = 0;
     let mut raw_cells: Vec<RawCell> = Vec::new();
     let mut word_defs: Vec<WordDef> = Vec::new();
-    let mut across_clues: Vec<RawClue> = Vec::new();
-    let mut down_clues: Vec<RawClue> = Vec::new();
+    // Each <clues> list with its title text
+    let mut clue_lists: Vec<(String, Vec<RawClue>)> = Vec::new();
+    let mut timer: Option<Timer> = None;
 
     // State tracking
     let mut in_metadata = false;
+    let mut in_applet_settings = false;
     let mut in_title = false;
     let mut in_creator = false;
     let mut in_copyright = false;
     let mut in_description = false;
     let mut in_clues = false;
-    let mut current_clue_direction: Option<bool> = None; // true = across
     let mut in_clue = false;
     let mut current_clue_word_id = String::new();
     let mut current_clue_number: u32 = 0;
+    let mut current_clue_label: Option<String> = None;
+    let mut current_clue_format: Option<String> = None;
     let mut current_clue_text = String::new();
     let mut in_clue_title = false;
 
@@ -110,6 +404,9 @@ fn parse_xml(data: &[u8]) -> Result<Puzzle, ParseError> {
     loop {
         match reader.read_event_into(&mut buf) {
             Ok(Event::Eof) => break,
+            Ok(Event::Start(e)) if e.local_name().as_ref() == b"applet-settings" => {
+                in_applet_settings = true;
+            }
             Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
                 let local_name = e.local_name();
                 let name = std::str::from_utf8(local_name.as_ref()).unwrap_or("");
@@ -131,6 +428,7 @@ fn parse_xml(data: &[u8]) -> Result<Puzzle, ParseError> {
                             }
                         }
                     }
+                    "timer" if in_applet_settings => timer = parse_timer_element(&e),
                     "cell" => {
                         let cell = parse_cell_element(&e)?;
                         raw_cells.push(cell);
@@ -140,26 +438,51 @@ fn parse_xml(data: &[u8]) -> Result<Puzzle, ParseError> {
                             word_defs.push(word);
                         }
                     }
+                    // <cells> only appear inside a <word>, extending it
+                    "cells" => {
+                        if let Some(word) = word_defs.last_mut() {
+                            word.cells.extend(parse_span_element(&e)?);
+                        }
+                    }
                     "clues" => {
                         in_clues = true;
-                        current_clue_direction = None;
+                        clue_lists.push((String::new(), Vec::new()));
                     }
                     "title" if in_clues => in_clue_title = true,
                     "clue" if in_clues => {
                         in_clue = true;
+                        // Keep the spaces around formatting tags in the clue
+                        reader.config_mut().trim_text(false);
                         current_clue_text.clear();
                         current_clue_word_id.clear();
                         current_clue_number = 0;
+                        current_clue_label = None;
+                        current_clue_format = None;
                         for attr in e.attributes().flatten() {
                             let key = std::str::from_utf8(attr.key.as_ref()).unwrap_or("");
                             let val = std::str::from_utf8(&attr.value).unwrap_or("");
                             match key {
                                 "word" => current_clue_word_id = val.to_string(),
-                                "number" => current_clue_number = val.parse().unwrap_or(0),
+                                "number" => match val.parse() {
+                                    Ok(number) => current_clue_number = number,
+                                    Err(_) if !val.is_empty() => {
+                                        current_clue_label = Some(val.to_string())
+                                    }
+                                    Err(_) => {}
+                                },
+                                "format" if !val.is_empty() => {
+                                    current_clue_format = Some(val.to_string())
+                                }
                                 _ => {}
                             }
                         }
                     }
+                    // Formatting inside a clue is kept as markup
+                    _ if in_clue => {
+                        current_clue_text.push('<');
+                        current_clue_text.push_str(&String::from_utf8_lossy(&e));
+                        current_clue_text.push('>');
+                    }
                     _ => {}
                 }
             }
@@ -174,15 +497,12 @@ fn parse_xml(data: &[u8]) -> Result<Puzzle, ParseError> {
                 } else if in_description {
                     description.push_str(&text);
                 } else if in_clue_title && in_clues {
-                    // Determine direction from title text
-                    let lower = text.to_lowercase();
-                    if lower.contains("across") {
-                        current_clue_direction = Some(true);
-                    } else if lower.contains("down") {
-                        current_clue_direction = Some(false);
+                    if let Some((list_title, _)) = clue_lists.last_mut() {
+                        list_title.push_str(&text);
                     }
                 } else if in_clue {
-                    current_clue_text.push_str(&text);
+                    // Left escaped: the clue's markup is decoded once, as a whole
+                    current_clue_text.push_str(&String::from_utf8_lossy(&e));
                 }
             }
             Ok(Event::End(e)) => {
@@ -190,29 +510,33 @@ fn parse_xml(data: &[u8]) -> Result<Puzzle, ParseError> {
                 let name = std::str::from_utf8(local_name.as_ref()).unwrap_or("");
                 match name {
                     "metadata" => in_metadata = false,
+                    "applet-settings" => in_applet_settings = false,
                     "title" if in_metadata => in_title = false,
                     "title" if in_clues => in_clue_title = false,
                     "creator" => in_creator = false,
                     "copyright" => in_copyright = false,
                     "description" => in_description = false,
-                    "clues" => {
-                        in_clues = false;
-                        current_clue_direction = None;
-                    }
+                    "clues" => in_clues = false,
                     "clue" => {
-                        if in_clue && current_clue_number > 0 {
+                        if in_clue && (current_clue_number > 0 || current_clue_label.is_some()) {
                             let raw = RawClue {
                                 word_id: current_clue_word_id.clone(),
                                 number: current_clue_number,
-                                text: strip_html_tags(&current_clue_text),
+                                label: current_clue_label.take(),
+                                format: current_clue_format.take(),
+                                text: current_clue_text.trim().to_string(),
                             };
-                            match current_clue_direction {
-                                Some(true) => across_clues.push(raw),
-                                Some(false) => down_clues.push(raw),
-                                None => {} // skip if direction unknown
+                            if let Some((_, list)) = clue_lists.last_mut() {
+                                list.push(raw);
                             }
                         }
                         in_clue = false;
+                        reader.config_mut().trim_text(true);
+                    }
+                    _ if in_clue => {
+                        current_clue_text.push_str("</");
+                        current_clue_text.push_str(name);
+                        current_clue_text.push('>');
                     }
                     _ => {}
                 }
@@ -233,22 +557,7 @@ fn parse_xml(data: &[u8]) -> Result<Puzzle, ParseError> {
     // Build grid from raw cells
     let w = grid_width as usize;
     let h = grid_height as usize;
-    let mut grid: Vec<Vec<Cell>> = vec![
-        vec![
-            Cell {
-                kind: CellKind::Letter,
-                number: None,
-                solution: None,
-                rebus_solution: None,
-                player_value: None,
-                is_circled: false,
-                was_incorrect: false,
-                is_revealed: false,
-            };
-            w
-        ];
-        h
-    ];
+    let mut grid: Vec<Vec<Cell>> = vec![vec![Cell::letter(); w]; h];
 
     let mut has_solution = false;
     for cell in &raw_cells {
@@ -258,17 +567,10 @@ fn parse_xml(data: &[u8]) -> Result<Puzzle, ParseError> {
             continue;
         }
 
-        if cell.is_block {
-            grid[row][col] = Cell {
-                kind: CellKind::Black,
-                number: None,
-                solution: None,
-                rebus_solution: None,
-                player_value: None,
-                is_circled: false,
-                was_incorrect: false,
-                is_revealed: false,
-            };
+        if cell.is_void {
+            grid[row][col] = Cell::void();
+        } else if cell.is_block {
+            grid[row][col] = Cell::black();
         } else {
             if cell.solution.is_some() {
                 has_solution = true;
@@ -285,15 +587,29 @@ fn parse_xml(data: &[u8]) -> Result<Puzzle, ParseError> {
                 (None, None)
             };
 
+            // A hint square starts out showing its solution
+            let is_given = cell.is_given && solution.is_some();
+            let player_value = if is_given {
+                rebus_solution.clone().or(solution.clone())
+            } else {
+                None
+            };
+
+            let is_shaded = cell
+                .background_color
+                .as_deref()
+                .is_some_and(|color| color.eq_ignore_ascii_case(SHADED_COLOR));
             grid[row][col] = Cell {
-                kind: CellKind::Letter,
                 number: cell.number,
                 solution,
                 rebus_solution,
-                player_value: None,
+                player_value,
+                is_given,
                 is_circled: cell.is_circled,
-                was_incorrect: false,
-                is_revealed: false,
+                bars: cell.bars,
+                is_shaded,
+                background_color: cell.background_color.clone(),
+                ..Cell::letter()
             };
         }
     }
@@ -301,9 +617,27 @@ fn parse_xml(data: &[u8]) -> Result<Puzzle, ParseError> {
     // Build word lookup: word_id -> WordDef
     let word_map: HashMap<String, &WordDef> = word_defs.iter().map(|w| (w.id.clone(), w)).collect();
 
-    // Build clue structs
-    let final_across = build_clues_from_raw(&across_clues, &word_map)?;
-    let final_down = build_clues_from_raw(&down_clues, &word_map)?;
+    // Build clue structs, telling the lists apart by their titles
+    let mut final_across = Vec::new();
+    let mut final_down = Vec::new();
+    let mut final_other = Vec::new();
+    for (list_title, raw_clues) in &clue_lists {
+        let label = list_title.trim();
+        let lower = label.to_lowercase();
+        if lower.contains("across") {
+            final_across.extend(build_clues_from_raw(raw_clues, &word_map, false)?);
+        } else if lower.contains("down") {
+            final_down.extend(build_clues_from_raw(raw_clues, &word_map, false)?);
+        } else {
+            let clues = build_clues_from_raw(raw_clues, &word_map, true)?;
+            let label = if label.is_empty() { "Clues" } else { label };
+            final_other.push(ClueList {
+                direction: label.to_string(),
+                label: label.to_string(),
+                clues,
+            });
+        }
+    }
 
     Ok(Puzzle {
         title,
@@ -316,9 +650,33 @@ fn parse_xml(data: &[u8]) -> Result<Puzzle, ParseError> {
         clues: Clues {
             across: final_across,
             down: final_down,
+            other: final_other,
         },
         has_solution,
         is_scrambled: false,
+        timer,
+    })
+}
+
+/// Parse the <timer> element of the applet settings: `initial-value` holds the elapsed
+/// seconds and `start-on-load` whether the timer is running.
+fn parse_timer_element(e: &quick_xml::events::BytesStart) -> Option<Timer> {
+    let mut elapsed_seconds = None;
+    let mut is_running = true;
+
+    for attr in e.attributes().flatten() {
+        let key = std::str::from_utf8(attr.key.as_ref()).unwrap_or("");
+        let val = std::str::from_utf8(&attr.value).unwrap_or("");
+        match key {
+            "initial-value" => elapsed_seconds = val.parse().ok(),
+            "start-on-load" => is_running = val != "false",
+            _ => {}
+        }
+    }
+
+    elapsed_seconds.map(|elapsed_seconds| Timer {
+        elapsed_seconds,
+        is_running,
     })
 }
 
@@ -329,7 +687,11 @@ fn parse_cell_element(e: &quick_xml::events::BytesStart) -> Result<RawCell, Pars
     let mut solution: Option<String> = None;
     let mut number: Option<u32> = None;
     let mut is_block = false;
+    let mut is_void = false;
+    let mut is_given = false;
     let mut is_circled = false;
+    let mut bars = Bars::default();
+    let mut background_color = None;
 
     for attr in e.attributes().flatten() {
         let key = std::str::from_utf8(attr.key.as_ref()).unwrap_or("");
@@ -340,7 +702,14 @@ fn parse_cell_element(e: &quick_xml::events::BytesStart) -> Result<RawCell, Pars
             "solution" => solution = Some(val.to_string()),
             "number" => number = val.parse().ok(),
             "type" if val == "block" => is_block = true,
+            "type" if val == "void" => is_void = true,
+            "hint" => is_given = val == "true",
             "background-shape" if val == "circle" => is_circled = true,
+            "top-bar" => bars.top = val == "true",
+            "right-bar" => bars.right = val == "true",
+            "bottom-bar" => bars.bottom = val == "true",
+            "left-bar" => bars.left = val == "true",
+            "background-color" => background_color = parse_color(val),
             _ => {}
         }
     }
@@ -351,60 +720,76 @@ fn parse_cell_element(e: &quick_xml::events::BytesStart) -> Result<RawCell, Pars
         solution,
         number,
         is_block,
+        is_void,
+        is_given,
         is_circled,
+        bars,
+        background_color,
     })
 }
 
 /// Parse a <word> XML element.
-/// Word elements define spans: `x="1-6" y="2"` (across) or `x="2" y="1-4"` (down).
+/// Word elements define spans: `x="1-6" y="2"` (across) or `x="2" y="1-4"` (down),
+/// or list their squares in `<cells>` children with the same attributes.
 fn parse_word_element(e: &quick_xml::events::BytesStart) -> Result<Option<WordDef>, ParseError> {
-    let mut id = String::new();
-    let mut x_attr = String::new();
-    let mut y_attr = String::new();
-
-    for attr in e.attributes().flatten() {
-        let key = std::str::from_utf8(attr.key.as_ref()).unwrap_or("");
-        let val = std::str::from_utf8(&attr.value).unwrap_or("");
-        match key {
-            "id" => id = val.to_string(),
-            "x" => x_attr = val.to_string(),
-            "y" => y_attr = val.to_string(),
-            _ => {}
-        }
-    }
-
+    let id = attribute(e, "id");
     if id.is_empty() {
         return Ok(None);
     }
-
-    // Determine if across (x has range) or down (y has range)
-    let (start_col, start_row, length) = if x_attr.contains('-') {
-        // Across: x="1-6", y="2"
-        let (start, end) = parse_range(&x_attr)?;
-        let row: usize = y_attr
-            .parse()
-            .map_err(|_| ParseError::Xml(format!("invalid word y: {}", y_attr)))?;
-        (start, row, (end - start + 1) as u8)
-    } else if y_attr.contains('-') {
-        // Down: x="2", y="1-4"
-        let (start, end) = parse_range(&y_attr)?;
-        let col: usize = x_attr
-            .parse()
-            .map_err(|_| ParseError::Xml(format!("invalid word x: {}", x_attr)))?;
-        (col, start, (end - start + 1) as u8)
-    } else {
-        // Single cell word — skip
-        return Ok(None);
-    };
-
     Ok(Some(WordDef {
         id,
-        start_col: start_col.saturating_sub(1), // 1-indexed to 0-indexed
-        start_row: start_row.saturating_sub(1),
-        length,
+        cells: parse_span_element(e)?,
     }))
 }
 
+/// The squares covered by the `x` and `y` attributes of a <word> or <cells>
+/// element, where either may be a range such as "1-6" or "6-1".
+fn parse_span_element(
+    e: &quick_xml::events::BytesStart,
+) -> Result<Vec<(usize, usize)>, ParseError> {
+    let (x_attr, y_attr) = (attribute(e, "x"), attribute(e, "y"));
+    if x_attr.is_empty() || y_attr.is_empty() {
+        return Ok(Vec::new());
+    }
+    let span = |s: &str| -> Result<Vec<usize>, ParseError> {
+        let (start, end) = if s.contains('-') {
+            parse_range(s)?
+        } else {
+            let n = s
+                .parse()
+                .map_err(|_| ParseError::Xml(format!("invalid coordinate: {}", s)))?;
+            (n, n)
+        };
+        if start == 0 || end == 0 {
+            return Err(ParseError::Xml(format!("invalid coordinate: {}", s)));
+        }
+        // 1-indexed to 0-indexed, keeping the direction of the range
+        Ok(if start <= end {
+            (start - 1..end).collect()
+        } else {
+            (end - 1..start).rev().collect()
+        })
+    };
+    let (cols, rows) = (span(&x_attr)?, span(&y_attr)?);
+    match (cols.as_slice(), rows.as_slice()) {
+        (&[col], rows) => Ok(rows.iter().map(|&row| (row, col)).collect()),
+        (cols, &[row]) => Ok(cols.iter().map(|&col| (row, col)).collect()),
+        _ => Err(ParseError::Xml(format!(
+            "span cannot cover both x=\"{}\" and y=\"{}\"",
+            x_attr, y_attr
+        ))),
+    }
+}
+
+/// The value of an attribute, or an empty string if it is missing.
+fn attribute(e: &quick_xml::events::BytesStart, key: &str) -> String {
+    e.attributes()
+        .flatten()
+        .find(|attr| attr.key.as_ref() == key.as_bytes())
+        .map(|attr| String::from_utf8_lossy(&attr.value).into_owned())
+        .unwrap_or_default()
+}
+
 /// Parse a range string like "1-6" into (start, end).
 fn parse_range(s: &str) -> Result<(usize, usize), ParseError> {
     let parts: Vec<&str> = s.split('-').collect();
@@ -421,43 +806,40 @@ fn parse_range(s: &str) -> Result<(usize, usize), ParseError> {
 }
 
 /// Build Clue structs from raw clues using word definitions.
+/// Clues without a known word are dropped unless `keep_unplaced` is set.
 fn build_clues_from_raw(
     raw_clues: &[RawClue],
     word_map: &HashMap<String, &WordDef>,
+    keep_unplaced: bool,
 ) -> Result<Vec<Clue>, ParseError> {
     let mut clues = Vec::new();
 
     for raw in raw_clues {
-        if let Some(word) = word_map.get(&raw.word_id) {
-            clues.push(Clue {
-                number: raw.number,
-                text: raw.text.clone(),
-                row: word.start_row,
-                col: word.start_col,
-                length: word.length,
-            });
-        }
+        let cells = match word_map.get(&raw.word_id) {
+            Some(word) if !word.cells.is_empty() => word.cells.clone(),
+            _ if keep_unplaced => Vec::new(),
+            _ => continue,
+        };
+        let (row, col) = cells.first().copied().unwrap_or_default();
+        let (text, rich_text) = clue_text(&raw.text);
+        clues.push(Clue {
+            number: raw.number,
+            text,
+            row,
+            col,
+            length: cells.len() as u8,
+            cells,
+            label: raw.label.clone(),
+            rich_text,
+            enumeration: raw.format.clone(),
+            references: Vec::new(),
+            continued: Vec::new(),
+        });
     }
 
     Ok(clues)
 }
 
-/// Strip HTML tags from a string (e.g., "<b>Across</b>" -> "Across").
-fn strip_html_tags(s: &str) -> String {
-    let mut result = String::with_capacity(s.len());
-    let mut in_tag = false;
-    for c in s.chars() {
-        if c == '<' {
-            in_tag = true;
-        } else if c == '>' {
-            in_tag = false;
-        } else if !in_tag {
-            result.push(c);
-        }
-    }
-    result
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -507,19 +889,246 @@ mod tests {
     }
 
     #[test]
-    fn test_strip_html_tags() {
-        assert_eq!(strip_html_tags("<b>Across</b>"), "Across");
-        assert_eq!(strip_html_tags("plain text"), "plain text");
+    fn test_parse_range() {
+        assert_eq!(parse_range("1-6").unwrap(), (1, 6));
+        assert_eq!(parse_range("10-13").unwrap(), (10, 13));
+        assert!(parse_range("invalid").is_err());
+    }
+
+    #[test]
+    fn test_write_round_trip() {
+        let data = include_bytes!("../tests/fixtures/puzzleme-example-crossword.xml");
+        let original = parse(data).unwrap();
+        let written = write(&original, false).unwrap();
+
+        let xml = String::from_utf8(written.clone()).unwrap();
+        assert!(xml.contains("<crossword-compiler-applet"));
+
+        let puzzle = parse(&written).unwrap();
+        assert_eq!(puzzle.title, original.title);
+        assert_eq!(puzzle.author, original.author);
+        assert_eq!(puzzle.copyright, original.copyright);
+        assert_eq!(puzzle.width, 13);
+        assert_eq!(puzzle.height, 13);
+        for (row, original_row) in original.grid.iter().enumerate() {
+            for (col, original_cell) in original_row.iter().enumerate() {
+                let cell = &puzzle.grid[row][col];
+                assert_eq!(cell.number, original_cell.number);
+                assert_eq!(cell.solution, original_cell.solution);
+            }
+        }
+        assert_eq!(puzzle.clues.across.len(), original.clues.across.len());
+        assert_eq!(puzzle.clues.down.len(), original.clues.down.len());
+        for (clue, original_clue) in puzzle.clues.down.iter().zip(&original.clues.down) {
+            assert_eq!(clue.number, original_clue.number);
+            assert_eq!(clue.text, original_clue.text);
+            assert_eq!((clue.row, clue.col), (original_clue.row, original_clue.col));
+            assert_eq!(clue.length, original_clue.length);
+        }
+    }
+
+    #[test]
+    fn test_write_zipped_with_rebus_and_circles() {
+        let data = include_bytes!("../tests/fixtures/puzzleme-example-crossword.xml");
+        let mut original = parse(data).unwrap();
+        original.grid[0][1].rebus_solution = Some("HEART".to_string());
+        original.grid[0][1].is_circled = true;
+        original.clues.across[0].text = "Fish & chips".to_string();
+
+        let written = write(&original, true).unwrap();
+        assert!(written.starts_with(ZIP_MAGIC));
+
+        let puzzle = parse(&written).unwrap();
+        assert_eq!(puzzle.grid[0][1].rebus_solution, Some("HEART".to_string()));
+        assert_eq!(puzzle.grid[0][1].solution, Some("H".to_string()));
+        assert!(puzzle.grid[0][1].is_circled);
+        assert!(!puzzle.grid[0][3].is_circled);
+        assert_eq!(puzzle.clues.across[0].text, "Fish & chips");
+    }
+
+    #[test]
+    fn test_bars_round_trip() {
+        let data = include_bytes!("../tests/fixtures/puzzleme-example-crossword.xml");
+        let mut original = parse(data).unwrap();
+        original.grid[0][0].bars.right = true;
+        original.grid[1][0].bars.top = true;
+
+        let written = write(&original, false).unwrap();
+        let xml = String::from_utf8(written.clone()).unwrap();
+        assert!(xml.contains(r#"<cell x="2" y="1""#));
+        assert_eq!(xml.matches("left-bar=\"true\"").count(), 1);
+        assert_eq!(xml.matches("top-bar=\"true\"").count(), 1);
+
+        // A right bar is written as the left bar of the next square.
+        let puzzle = parse(&written).unwrap();
+        assert!(puzzle.grid[0][1].bars.left);
+        assert!(puzzle.grid[1][0].bars.top);
+        assert!(!puzzle.grid[0][0].bars.right);
+    }
+
+    #[test]
+    fn test_background_color_round_trip() {
+        let data = include_bytes!("../tests/fixtures/puzzleme-example-crossword.xml");
+        let mut original = parse(data).unwrap();
+        assert!(original.grid[0][1].background_color.is_none());
+        original.grid[0][1].background_color = Some("#FF8000".into());
+        original.grid[0][3].is_shaded = true;
+        original.grid[1][0].is_shaded = true;
+        original.grid[1][0].background_color = Some("#FF8000".into());
+
+        let written = write(&original, false).unwrap();
+        let xml = String::from_utf8(written.clone()).unwrap();
+        assert!(xml.contains(r##"background-color="#FF8000""##));
+
+        // Shaded squares are written with a gray background and read back
+        // as shaded.
+        assert!(xml.contains(&format!(r#"background-color="{}""#, SHADED_COLOR)));
+        let puzzle = parse(&written).unwrap();
+        assert_eq!(
+            puzzle.grid[0][1].background_color.as_deref(),
+            Some("#FF8000")
+        );
+        assert!(!puzzle.grid[0][1].is_shaded);
+        assert!(puzzle.grid[0][3].is_shaded);
+        // A shaded square's own color is written in place of the gray.
         assert_eq!(
-            strip_html_tags("Old-fashioned record player: Hyph."),
-            "Old-fashioned record player: Hyph."
+            puzzle.grid[1][0].background_color.as_deref(),
+            Some("#FF8000")
+        );
+
+        // Any spelling of the gray marks a square as shaded, keeping its color.
+        let xml = xml.replace(SHADED_COLOR, "#dcdcdc");
+        let puzzle = parse(xml.as_bytes()).unwrap();
+        assert!(puzzle.grid[0][3].is_shaded);
+        assert_eq!(
+            puzzle.grid[0][3].background_color.as_deref(),
+            Some(SHADED_COLOR)
         );
     }
 
     #[test]
-    fn test_parse_range() {
-        assert_eq!(parse_range("1-6").unwrap(), (1, 6));
-        assert_eq!(parse_range("10-13").unwrap(), (10, 13));
-        assert!(parse_range("invalid").is_err());
+    fn test_void_cells_round_trip() {
+        let data = include_str!("../tests/fixtures/puzzleme-example-crossword.xml").replace(
+            r#"<cell x="1" y="1" type="block"/>"#,
+            r#"<cell x="1" y="1" type="void"/>"#,
+        );
+        let original = parse(data.as_bytes()).unwrap();
+        assert!(matches!(original.grid[0][0].kind, CellKind::Void));
+        assert!(matches!(original.grid[2][0].kind, CellKind::Black));
+
+        let puzzle = parse(&write(&original, false).unwrap()).unwrap();
+        assert!(matches!(puzzle.grid[0][0].kind, CellKind::Void));
+        assert!(matches!(puzzle.grid[2][0].kind, CellKind::Black));
+    }
+
+    #[test]
+    fn test_hint_cells_round_trip() {
+        let data = include_str!("../tests/fixtures/puzzleme-example-crossword.xml").replace(
+            r#"<cell x="2" y="1" solution="H" number="1"/>"#,
+            r#"<cell x="2" y="1" solution="H" number="1" hint="true"/>"#,
+        );
+        let original = parse(data.as_bytes()).unwrap();
+        assert!(original.grid[0][1].is_given);
+        assert_eq!(original.grid[0][1].player_value.as_deref(), Some("H"));
+        assert!(!original.grid[1][1].is_given);
+
+        let puzzle = parse(&write(&original, false).unwrap()).unwrap();
+        assert!(puzzle.grid[0][1].is_given);
+        assert!(!puzzle.grid[1][1].is_given);
+    }
+
+    #[test]
+    fn test_named_clue_list_round_trip() {
+        let data = include_str!("../tests/fixtures/puzzleme-example-crossword.xml").replace(
+            "</crossword>",
+            r#"<clues><title><b>Bonus</b></title><clue number="A">Theme hint</clue></clues></crossword>"#,
+        );
+        let original = parse(data.as_bytes()).unwrap();
+        assert_eq!(original.clues.across.len(), 12);
+        assert_eq!(original.clues.other.len(), 1);
+        assert_eq!(original.clues.other[0].label, "Bonus");
+        let bonus = &original.clues.other[0].clues[0];
+        assert_eq!(bonus.label.as_deref(), Some("A"));
+        assert_eq!((bonus.text.as_str(), bonus.length), ("Theme hint", 0));
+
+        let puzzle = parse(&write(&original, false).unwrap()).unwrap();
+        assert_eq!(puzzle.clues.down.len(), 10);
+        assert_eq!(puzzle.clues.other[0].label, "Bonus");
+        assert_eq!(puzzle.clues.other[0].clues[0].text, "Theme hint");
+    }
+
+    #[test]
+    fn test_format_round_trip() {
+        let data = include_bytes!("../tests/fixtures/puzzleme-example-crossword.xml");
+        let original = parse(data).unwrap();
+        assert_eq!(original.clues.across[7].enumeration.as_deref(), Some("3-4"));
+        assert_eq!(original.clues.down[4].enumeration.as_deref(), Some("5-3,5"));
+
+        let puzzle = parse(&write(&original, false).unwrap()).unwrap();
+        assert_eq!(puzzle.clues.across[7].enumeration.as_deref(), Some("3-4"));
+        assert_eq!(puzzle.clues.down[1].enumeration.as_deref(), Some("8,5"));
+    }
+
+    #[test]
+    fn test_formatted_clue_round_trip() {
+        let data = include_str!("../tests/fixtures/puzzleme-example-crossword.xml").replace(
+            ">One under, in golf<",
+            "><i>One</i> under, in H<sub>2</sub>O &lt; &amp;amp; golf<",
+        );
+        let original = parse(data.as_bytes()).unwrap();
+        let clue = &original.clues.across[0];
+        assert_eq!(clue.text, "One under, in H2O < &amp; golf");
+        assert!(clue.rich_text[0].italic);
+        assert_eq!(clue.rich_text[1].text, " under, in H");
+        assert!(clue.rich_text[2].subscript);
+
+        let written = write(&original, false).unwrap();
+        let xml = String::from_utf8(written.clone()).unwrap();
+        assert!(xml.contains("><i>One</i> under, in H<sub>2</sub>O &lt; &amp;amp; golf</clue>"));
+        let puzzle = parse(&written).unwrap();
+        assert_eq!(puzzle.clues.across[0].text, clue.text);
+        assert_eq!(puzzle.clues.across[0].rich_text, clue.rich_text);
+        assert!(puzzle.clues.across[1].rich_text.is_empty());
+    }
+
+    #[test]
+    fn test_word_with_cells_round_trip() {
+        let data = include_str!("../tests/fixtures/puzzleme-example-crossword.xml").replace(
+            r#"<word id="1" x="1-6" y="2"/>"#,
+            r#"<word id="1"><cells x="1-3" y="2"/><cells x="6" y="4-3"/></word>"#,
+        );
+        let original = parse(data.as_bytes()).unwrap();
+        let clue = &original.clues.across[0];
+        assert_eq!(clue.cells, vec![(1, 0), (1, 1), (1, 2), (3, 5), (2, 5)]);
+        assert_eq!((clue.row, clue.col, clue.length), (1, 0, 5));
+        assert_eq!(original.clues.across[1].cells.len(), 6);
+
+        let puzzle = parse(&write(&original, false).unwrap()).unwrap();
+        assert_eq!(puzzle.clues.across[0].cells, clue.cells);
+        assert_eq!(puzzle.clues.across[1].cells, original.clues.across[1].cells);
+    }
+
+    #[test]
+    fn test_timer_round_trip() {
+        let data = include_bytes!("../tests/fixtures/puzzleme-example-crossword.xml");
+        let mut original = parse(data).unwrap();
+        assert!(original.timer.is_none());
+        original.timer = Some(Timer {
+            elapsed_seconds: 300,
+            is_running: false,
+        });
+
+        let written = write(&original, false).unwrap();
+        let xml = String::from_utf8(written.clone()).unwrap();
+        assert!(xml.contains(r#"<timer initial-value="300" start-on-load="false"/>"#));
+
+        let puzzle = parse(&written).unwrap();
+        assert_eq!(puzzle.timer, original.timer);
+
+        // Only the applet's own timer setting counts
+        let outside = xml
+            .replace("<applet-settings>", "")
+            .replace("</applet-settings>", "");
+        assert!(parse(outside.as_bytes()).unwrap().timer.is_none());
     }
 }
@@ -1,22 +1,179 @@
 pub mod error;
+pub mod exolve;
+pub mod guardian;
 pub mod ipuz;
 pub mod jpz;
+mod markup;
+mod numbering;
+pub mod nyt;
 pub mod puz;
+pub mod puzzleme;
+mod text;
+pub mod txt;
 pub mod types;
+pub mod uclick;
+pub mod xd;
 
 pub use error::ParseError;
-pub use types::{Cell, CellKind, Clue, Clues, Puzzle};
+pub use types::{Bars, Cell, CellKind, Clue, ClueList, ClueRef, Clues, Puzzle, TextRun, Timer};
+
+/// A supported crossword file format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Across Lite binary (.puz).
+    Puz,
+    /// ipuz JSON (.ipuz).
+    Ipuz,
+    /// Crossword Compiler XML, optionally zipped (.jpz, .xml).
+    Jpz,
+    /// Across Lite text (.txt).
+    Txt,
+    /// xd plain text (.xd).
+    Xd,
+    /// Exolve text, usually embedded in an HTML page (.exolve, .html).
+    Exolve,
+    /// PuzzleMe (Amuse Labs) `rawc` blob or its decoded JSON (.rawc).
+    PuzzleMe,
+    /// Newspaper web app JSON, as used by the New York Times (.json).
+    Nyt,
+    /// Guardian-style crossword JSON (.json).
+    Guardian,
+    /// uclick / Universal syndication XML (.xml).
+    Uclick,
+}
+
+impl Format {
+    /// The format conventionally used by a file extension.
+    pub fn from_extension(extension: &str) -> Option<Format> {
+        match extension.to_lowercase().as_str() {
+            "puz" => Some(Format::Puz),
+            "ipuz" => Some(Format::Ipuz),
+            "jpz" | "xml" => Some(Format::Jpz),
+            "txt" => Some(Format::Txt),
+            "xd" => Some(Format::Xd),
+            "exolve" | "html" | "htm" => Some(Format::Exolve),
+            "rawc" => Some(Format::PuzzleMe),
+            // Several formats are saved as .json; `parse` tells them apart
+            "json" => Some(Format::Ipuz),
+            _ => None,
+        }
+    }
+
+    /// The format of a file with the given extension and contents.
+    ///
+    /// An extension that names one format is trusted, so a loose content
+    /// heuristic cannot send a valid file to the wrong parser. Extensions
+    /// shared by several formats (.xml, .json, .txt) are told apart by the
+    /// contents, and files with an unknown extension are detected from the
+    /// contents alone.
+    pub fn from_file(extension: &str, data: &[u8]) -> Option<Format> {
+        let Some(format) = Format::from_extension(extension) else {
+            return Format::detect(data);
+        };
+        let shared: &[Format] = match extension.to_lowercase().as_str() {
+            "xml" => &[Format::Uclick],
+            "json" => &[Format::PuzzleMe, Format::Nyt, Format::Guardian],
+            "txt" => &[Format::Xd],
+            _ => &[],
+        };
+        Some(
+            Format::detect(data)
+                .filter(|f| shared.contains(f))
+                .unwrap_or(format),
+        )
+    }
+
+    /// Detect the format from file contents, ignoring any extension.
+    pub fn detect(data: &[u8]) -> Option<Format> {
+        if data.get(2..14) == Some(b"ACROSS&DOWN\0") {
+            return Some(Format::Puz);
+        }
+        if data.starts_with(b"PK\x03\x04") {
+            return Some(Format::Jpz);
+        }
+
+        let text = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+        let start = text.iter().position(|b| !b.is_ascii_whitespace())?;
+        match text[start] {
+            b'<' if contains(text, b"<crossword-compiler") => Some(Format::Jpz),
+            b'<' if contains(text, b"<crossword") && contains(text, b"<AllAnswer") => {
+                Some(Format::Uclick)
+            }
+            b'{' if is_ipuz_json(text) => Some(Format::Ipuz),
+            b'{' if contains(text, b"\"placedWords\"") => Some(Format::PuzzleMe),
+            b'{' if contains(text, b"\"body\"") && contains(text, b"\"dimensions\"") => {
+                Some(Format::Nyt)
+            }
+            b'{' if contains(text, b"\"entries\"") && contains(text, b"\"position\"") => {
+                Some(Format::Guardian)
+            }
+            _ if text[start..].starts_with(b"<ACROSS PUZZLE") => Some(Format::Txt),
+            _ if contains(text, b"exolve-begin") => Some(Format::Exolve),
+            _ if xd::looks_like_xd(&puz::decode_string(text)) => Some(Format::Xd),
+            // Base64 of a JSON object, as in a saved PuzzleMe `rawc` blob.
+            _ if text[start..].starts_with(b"eyJ") => Some(Format::PuzzleMe),
+            _ => None,
+        }
+    }
+}
 
 /// Parse crossword puzzle bytes, auto-detecting format by extension.
+///
+/// Extensions shared by several formats, such as `.xml` for jpz and uclick
+/// files, are told apart by their contents (see [`Format::from_file`]).
 pub fn parse(data: &[u8], extension: &str) -> Result<Puzzle, ParseError> {
-    match extension.to_lowercase().as_str() {
-        "puz" => puz::parse(data),
-        "ipuz" => ipuz::parse(data),
-        "jpz" | "xml" => jpz::parse(data),
-        ext => Err(ParseError::UnsupportedFormat(ext.into())),
+    if Format::from_extension(extension).is_none() {
+        return Err(ParseError::UnsupportedFormat(extension.to_lowercase()));
+    }
+    let format = Format::from_file(extension, data)
+        .ok_or_else(|| ParseError::UnsupportedFormat(extension.to_lowercase()))?;
+    parse_format(data, format)
+}
+
+/// Parse crossword puzzle bytes in a known format.
+pub fn parse_format(data: &[u8], format: Format) -> Result<Puzzle, ParseError> {
+    match format {
+        Format::Puz => puz::parse(data),
+        Format::Ipuz => ipuz::parse(data),
+        Format::Jpz => jpz::parse(data),
+        Format::Txt => txt::parse(data),
+        Format::Xd => xd::parse(data),
+        Format::Exolve => exolve::parse(data),
+        Format::PuzzleMe => puzzleme::parse(data),
+        Format::Nyt => nyt::parse(data),
+        Format::Guardian => guardian::parse(data),
+        Format::Uclick => uclick::parse(data),
     }
 }
 
+/// Parse crossword puzzle bytes, detecting the format from the contents.
+/// Returns the detected format together with the puzzle.
+pub fn parse_auto(data: &[u8]) -> Result<(Format, Puzzle), ParseError> {
+    let format = Format::detect(data)
+        .ok_or_else(|| ParseError::UnsupportedFormat("unrecognized file contents".into()))?;
+    Ok((format, parse_format(data, format)?))
+}
+
+/// Whether `needle` occurs anywhere in `haystack`.
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+/// Whether JSON data declares an ipuz `kind`.
+fn is_ipuz_json(data: &[u8]) -> bool {
+    serde_json::from_slice::<serde_json::Value>(data)
+        .ok()
+        .and_then(|json| json.get("kind")?.as_array().cloned())
+        .is_some_and(|kinds| {
+            kinds
+                .iter()
+                .filter_map(|k| k.as_str())
+                .any(|k| k.starts_with("http://ipuz.org/"))
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -58,6 +215,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_xml_extension_sniffs_uclick() {
+        let uclick = br#"<crossword><Width v="2" /><Height v="1" /><AllAnswer v="AB" />
+            <across><a1 cn="1" c="Clue" n="1" a="AB" /></across></crossword>"#;
+        assert_eq!(Format::detect(uclick), Some(Format::Uclick));
+        let puzzle = parse(uclick, "xml").unwrap();
+        assert_eq!(puzzle.clues.across[0].text, "Clue");
+
+        let jpz = include_bytes!("../tests/fixtures/puzzleme-example-crossword.xml");
+        assert!(parse(jpz, "xml").is_ok());
+    }
+
+    #[test]
+    fn test_json_extension_sniffs_format() {
+        assert_eq!(Format::from_extension("JSON"), Some(Format::Ipuz));
+        let guardian = br#"{"dimensions": {"cols": 2, "rows": 1}, "entries": [{"id": "1-across",
+            "number": 1, "clue": "Clue (2)", "direction": "across", "length": 2,
+            "position": {"x": 0, "y": 0}, "solution": "AB"}]}"#;
+        assert_eq!(Format::detect(guardian), Some(Format::Guardian));
+        let puzzle = parse(guardian, "json").unwrap();
+        assert_eq!(puzzle.clues.across.len(), 1);
+    }
+
+    #[test]
+    fn test_from_file_trusts_unambiguous_extensions() {
+        let xd = b"AB\nCD\n\n\nA1. Clue ~ AB\n";
+        assert_eq!(Format::from_file("xd", xd), Some(Format::Xd));
+        assert_eq!(Format::from_file("txt", xd), Some(Format::Xd));
+        assert_eq!(Format::from_file("", xd), Some(Format::Xd));
+        // A named format wins over what the contents look like
+        assert_eq!(Format::from_file("puz", xd), Some(Format::Puz));
+        assert_eq!(
+            Format::from_file("ipuz", b"eyJ0aXRsZSI6ICJNaW5pIn0="),
+            Some(Format::Ipuz)
+        );
+        assert_eq!(
+            Format::from_file("txt", b"<ACROSS PUZZLE>"),
+            Some(Format::Txt)
+        );
+        assert_eq!(Format::from_file("pdf", b"plain text"), None);
+    }
+
     #[test]
     fn test_ipuz_extension_routes_correctly() {
         let result = parse(b"{}", "ipuz");
@@ -68,4 +267,67 @@ mod tests {
             "ipuz extension should route to ipuz parser"
         );
     }
+
+    #[test]
+    fn test_detect_fixture_formats() {
+        let puz = include_bytes!("../tests/fixtures/puzzleme-example-crossword.puz");
+        let jpz = include_bytes!("../tests/fixtures/puzzleme-example-crossword.jpz");
+        let xml = include_bytes!("../tests/fixtures/puzzleme-example-crossword.xml");
+        assert_eq!(Format::detect(puz), Some(Format::Puz));
+        assert_eq!(Format::detect(jpz), Some(Format::Jpz));
+        assert_eq!(Format::detect(xml), Some(Format::Jpz));
+    }
+
+    #[test]
+    fn test_detect_ipuz_json() {
+        let ipuz =
+            br#"  {"version": "http://ipuz.org/v2", "kind": ["http://ipuz.org/crossword#1"]}"#;
+        assert_eq!(Format::detect(ipuz), Some(Format::Ipuz));
+        assert_eq!(Format::detect(br#"{"kind": "other"}"#), None);
+        assert_eq!(Format::detect(b"plain text"), None);
+        assert_eq!(Format::detect(b""), None);
+        assert_eq!(
+            Format::detect(b"\n<ACROSS PUZZLE V2>\n<TITLE>\n"),
+            Some(Format::Txt)
+        );
+        assert_eq!(
+            Format::detect(b"Title: Sample\nAuthor: Someone\n\n\nAB\n\n\nA1. Clue ~ AB\n"),
+            Some(Format::Xd)
+        );
+        assert_eq!(
+            Format::detect(b"Author: Someone\nDate: 2024-01-01\n\n\nA#\nBC\n\n\nA3. Clue ~ BC\n"),
+            Some(Format::Xd)
+        );
+        assert_eq!(
+            Format::detect(b"AB\nCD\n\n\nA1. Clue ~ AB\n"),
+            Some(Format::Xd)
+        );
+        assert_eq!(
+            Format::detect(b"Title: Not xd\n\n\nSome prose here\n"),
+            None
+        );
+        assert_eq!(
+            Format::detect(b"<html>\n<script>\nexolve-begin\n"),
+            Some(Format::Exolve)
+        );
+        assert_eq!(
+            Format::detect(br#"{"w": 3, "h": 3, "box": [], "placedWords": []}"#),
+            Some(Format::PuzzleMe)
+        );
+        assert_eq!(
+            Format::detect(b"eyJ0aXRsZSI6ICJNaW5pIn0="),
+            Some(Format::PuzzleMe)
+        );
+    }
+
+    #[test]
+    fn test_parse_auto_ignores_extension() {
+        let data = include_bytes!("../tests/fixtures/puzzleme-example-crossword.jpz");
+        let (format, puzzle) = parse_auto(data).unwrap();
+        assert_eq!(format, Format::Jpz);
+        assert!(!puzzle.clues.across.is_empty());
+
+        let err = parse_auto(b"not a puzzle").unwrap_err();
+        assert!(matches!(err, ParseError::UnsupportedFormat(_)));
+    }
 }
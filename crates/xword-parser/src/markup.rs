@@ -0,0 +1,344 @@
+//! HTML-style markup in clue text.
+//!
+//! Clue files mark italics, bold, subscripts, superscripts and strikethrough
+//! with a small set of HTML tags. A few other common tags are dropped and
+//! their content kept; anything else that looks like a tag is text.
+
+use crate::types::TextRun;
+
+/// The plain text of a marked-up clue, with its formatted runs when any of
+/// the text is formatted.
+pub(crate) fn clue_text(s: &str) -> (String, Vec<TextRun>) {
+    let runs = parse_markup(s);
+    let text = plain_text(&runs);
+    if runs.iter().all(|run| !run.is_formatted()) {
+        return (text, Vec::new());
+    }
+    (text, runs)
+}
+
+/// Strip HTML tags from a string (e.g., "<b>Across</b>" -> "Across").
+pub(crate) fn strip_html_tags(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut in_tag = false;
+    for c in s.chars() {
+        if c == '<' {
+            in_tag = true;
+        } else if c == '>' {
+            in_tag = false;
+        } else if !in_tag {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Split marked-up text into runs, merging neighbors with the same formatting.
+pub(crate) fn parse_markup(s: &str) -> Vec<TextRun> {
+    let mut runs: Vec<TextRun> = Vec::new();
+    // Open tags with the formatting in effect inside each
+    let mut open: Vec<(String, TextRun)> = Vec::new();
+    let mut text_start = 0;
+    let mut search = 0;
+
+    while let Some(offset) = s[search..].find('<') {
+        let start = search + offset;
+        let Some(tag) = known_tag(&s[start..]) else {
+            // A '<' that does not open a known tag is ordinary text
+            search = start + 1;
+            continue;
+        };
+        let style = open
+            .last()
+            .map(|(_, style)| style.clone())
+            .unwrap_or_default();
+        push_text(&mut runs, &style, &decode_entities(&s[text_start..start]));
+        search = start + tag.len() + 2;
+        text_start = search;
+
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim().to_lowercase();
+            if let Some(pos) = open.iter().rposition(|(open_name, _)| *open_name == name) {
+                open.truncate(pos);
+            }
+            continue;
+        }
+        let (name, attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+        let name = name.trim_end_matches('/').to_lowercase();
+        if name == "br" {
+            push_text(&mut runs, &style, " ");
+            continue;
+        }
+        if tag.ends_with('/') {
+            continue;
+        }
+        let mut inner = style;
+        match name.as_str() {
+            "i" | "em" => inner.italic = true,
+            "b" | "strong" => inner.bold = true,
+            "sub" => inner.subscript = true,
+            "sup" => inner.superscript = true,
+            "s" | "strike" | "del" => inner.strikethrough = true,
+            "span" => {
+                let css = attributes.to_lowercase();
+                inner.italic |= css.contains("italic");
+                inner.bold |= css.contains("bold");
+                inner.strikethrough |= css.contains("line-through");
+            }
+            _ => {}
+        }
+        open.push((name, inner));
+    }
+
+    let style = open
+        .last()
+        .map(|(_, style)| style.clone())
+        .unwrap_or_default();
+    push_text(&mut runs, &style, &decode_entities(&s[text_start..]));
+    runs
+}
+
+/// Tags read from clue text. Those without formatting of their own are
+/// dropped and their content kept.
+const KNOWN_TAGS: &[&str] = &[
+    "i", "em", "b", "strong", "sub", "sup", "s", "strike", "del", "span", "br", "p", "div", "font",
+    "a", "u",
+];
+
+/// The inside of the tag at the start of `s`, when `s` opens or closes a
+/// known tag. As in HTML, the name must follow the '<' or "</" directly, so
+/// "a < b > c" is text.
+fn known_tag(s: &str) -> Option<&str> {
+    let tag = &s[1..s.find('>')?];
+    let name = tag.strip_prefix('/').unwrap_or(tag);
+    let name = &name[..name
+        .find(|c: char| c.is_whitespace() || c == '/')
+        .unwrap_or(name.len())];
+    KNOWN_TAGS
+        .iter()
+        .any(|known| known.eq_ignore_ascii_case(name))
+        .then_some(tag)
+}
+
+/// Append text to the last run if it has the same formatting, else start a run.
+fn push_text(runs: &mut Vec<TextRun>, style: &TextRun, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    match runs.last_mut() {
+        Some(last) if last.same_format(style) => last.text.push_str(text),
+        _ => runs.push(TextRun {
+            text: text.to_string(),
+            ..style.clone()
+        }),
+    }
+}
+
+/// The text of runs without their formatting.
+pub(crate) fn plain_text(runs: &[TextRun]) -> String {
+    runs.iter().map(|run| run.text.as_str()).collect()
+}
+
+/// Render runs as HTML-style markup, escaping the text.
+pub(crate) fn to_markup(runs: &[TextRun]) -> String {
+    let mut out = String::new();
+    for run in runs {
+        let tags = run.tags();
+        for tag in &tags {
+            out.push_str(&format!("<{}>", tag));
+        }
+        out.push_str(&escape(&run.text));
+        for tag in tags.iter().rev() {
+            out.push_str(&format!("</{}>", tag));
+        }
+    }
+    out
+}
+
+/// Escape the characters that would otherwise be read as markup.
+pub(crate) fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Decode the named and numeric character references found in clue text.
+fn decode_entities(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest.find(';').and_then(|end| {
+            let c = match &rest[1..end] {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                "nbsp" => ' ',
+                entity => {
+                    if let Some(c) = named_entity(entity) {
+                        return Some((c, end + 1));
+                    }
+                    let code = match entity.strip_prefix("#x").or(entity.strip_prefix("#X")) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => entity.strip_prefix('#')?.parse().ok()?,
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((c, end + 1))
+        });
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Entity names for U+00A0 to U+00FF, in code point order.
+const LATIN_1_ENTITIES: [&str; 96] = [
+    "nbsp", "iexcl", "cent", "pound", "curren", "yen", "brvbar", "sect", "uml", "copy", "ordf",
+    "laquo", "not", "shy", "reg", "macr", "deg", "plusmn", "sup2", "sup3", "acute", "micro",
+    "para", "middot", "cedil", "sup1", "ordm", "raquo", "frac14", "frac12", "frac34", "iquest",
+    "Agrave", "Aacute", "Acirc", "Atilde", "Auml", "Aring", "AElig", "Ccedil", "Egrave", "Eacute",
+    "Ecirc", "Euml", "Igrave", "Iacute", "Icirc", "Iuml", "ETH", "Ntilde", "Ograve", "Oacute",
+    "Ocirc", "Otilde", "Ouml", "times", "Oslash", "Ugrave", "Uacute", "Ucirc", "Uuml", "Yacute",
+    "THORN", "szlig", "agrave", "aacute", "acirc", "atilde", "auml", "aring", "aelig", "ccedil",
+    "egrave", "eacute", "ecirc", "euml", "igrave", "iacute", "icirc", "iuml", "eth", "ntilde",
+    "ograve", "oacute", "ocirc", "otilde", "ouml", "divide", "oslash", "ugrave", "uacute", "ucirc",
+    "uuml", "yacute", "thorn", "yuml",
+];
+
+/// Other named characters common in clues: punctuation and a few letters.
+const OTHER_ENTITIES: &[(&str, char)] = &[
+    ("ndash", '–'),
+    ("mdash", '—'),
+    ("lsquo", '‘'),
+    ("rsquo", '’'),
+    ("sbquo", '‚'),
+    ("ldquo", '“'),
+    ("rdquo", '”'),
+    ("bdquo", '„'),
+    ("lsaquo", '‹'),
+    ("rsaquo", '›'),
+    ("hellip", '…'),
+    ("bull", '•'),
+    ("dagger", '†'),
+    ("Dagger", '‡'),
+    ("prime", '′'),
+    ("Prime", '″'),
+    ("permil", '‰'),
+    ("trade", '™'),
+    ("euro", '€'),
+    ("minus", '−'),
+    ("OElig", 'Œ'),
+    ("oelig", 'œ'),
+    ("Scaron", 'Š'),
+    ("scaron", 'š'),
+    ("Yuml", 'Ÿ'),
+    ("fnof", 'ƒ'),
+];
+
+/// The character a named entity stands for, if it is one clue files use.
+fn named_entity(name: &str) -> Option<char> {
+    if let Some(pos) = LATIN_1_ENTITIES.iter().position(|entity| *entity == name) {
+        return char::from_u32(0xA0 + pos as u32);
+    }
+    OTHER_ENTITIES
+        .iter()
+        .find(|(entity, _)| *entity == name)
+        .map(|(_, c)| *c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(text: &str) -> TextRun {
+        TextRun {
+            text: text.to_string(),
+            ..TextRun::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_markup() {
+        let runs = parse_markup("<i>Hamlet</i> or H<sub>2</sub>O &amp; <b>more</b>");
+        assert_eq!(
+            runs,
+            vec![
+                TextRun {
+                    italic: true,
+                    ..run("Hamlet")
+                },
+                run(" or H"),
+                TextRun {
+                    subscript: true,
+                    ..run("2")
+                },
+                run("O & "),
+                TextRun {
+                    bold: true,
+                    ..run("more")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_and_styled_tags() {
+        let runs = parse_markup(
+            r#"<b>x<sup>2</sup></b><span style="font-style: italic">it</span><strike>no</strike>"#,
+        );
+        assert!(runs[0].bold && !runs[0].superscript);
+        assert!(runs[1].bold && runs[1].superscript);
+        assert!(runs[2].italic && !runs[2].bold);
+        assert!(runs[3].strikethrough);
+        assert_eq!(plain_text(&runs), "x2itno");
+    }
+
+    #[test]
+    fn test_clue_text() {
+        assert_eq!(clue_text("Plain <br/>text"), ("Plain  text".into(), vec![]));
+        assert_eq!(clue_text("a < b"), ("a < b".into(), vec![]));
+        assert_eq!(clue_text("a < b > c"), ("a < b > c".into(), vec![]));
+        assert_eq!(
+            clue_text("&#233;t&eacute; &mdash; &Ntilde;&hellip;&bogus;"),
+            ("été — Ñ…&bogus;".into(), vec![])
+        );
+        let (text, runs) = clue_text("<em>Emma</em> author");
+        assert_eq!(text, "Emma author");
+        assert_eq!(runs.len(), 2);
+        let (text, runs) = clue_text("<i>x</i> <y> <FONT color=red>z</FONT>");
+        assert_eq!(text, "x <y> z");
+        assert_eq!(runs.len(), 2);
+    }
+
+    #[test]
+    fn test_strip_html_tags() {
+        assert_eq!(strip_html_tags("<b>Across</b>"), "Across");
+        assert_eq!(strip_html_tags("plain text"), "plain text");
+        assert_eq!(
+            strip_html_tags("Old-fashioned record player: Hyph."),
+            "Old-fashioned record player: Hyph."
+        );
+    }
+
+    #[test]
+    fn test_markup_round_trip() {
+        let source = "<i>Dr. No</i> &amp; H<sub>2</sub>O";
+        let runs = parse_markup(source);
+        assert_eq!(to_markup(&runs), source);
+        assert_eq!(parse_markup(&to_markup(&runs)), runs);
+    }
+}
@@ -0,0 +1,329 @@
+//! Standard crossword numbering for formats that only store the grid.
+
+use crate::error::ParseError;
+use crate::types::{Bars, Cell, CellKind, Clue};
+
+/// A word slot found while numbering a grid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Slot {
+    pub number: u32,
+    pub row: usize,
+    pub col: usize,
+    pub length: u8,
+}
+
+impl Slot {
+    /// The squares of the word, stepping by (d_row, d_col) from its start.
+    pub fn cells(&self, (d_row, d_col): (usize, usize)) -> Vec<(usize, usize)> {
+        run_cells(self.row, self.col, self.length, d_row, d_col)
+    }
+}
+
+/// The (row, col) step between squares of an "across" or "down" word.
+pub(crate) fn direction_step(direction: &str) -> (usize, usize) {
+    if direction == "across" {
+        (0, 1)
+    } else {
+        (1, 0)
+    }
+}
+
+/// The squares of a straight run of `length` cells from (row, col).
+pub(crate) fn run_cells(
+    row: usize,
+    col: usize,
+    length: u8,
+    d_row: usize,
+    d_col: usize,
+) -> Vec<(usize, usize)> {
+    (0..length as usize)
+        .map(|i| (row + i * d_row, col + i * d_col))
+        .collect()
+}
+
+/// A copy of the grid with its bars removed, numbered as a format without
+/// bars will number it when the file is read back.
+pub(crate) fn number_grid_without_bars(grid: &[Vec<Cell>]) -> (Vec<Slot>, Vec<Slot>) {
+    let mut grid = grid.to_vec();
+    for cell in grid.iter_mut().flatten() {
+        cell.bars = Bars::default();
+    }
+    number_grid(&mut grid)
+}
+
+/// Number the grid left to right, top to bottom, writing `Cell::number`.
+///
+/// A cell gets a number when it starts an across or down word of at least
+/// two letters. Words end at black and void squares and at bars between
+/// cells.
+/// Returns the across and down slots in number order.
+pub(crate) fn number_grid(grid: &mut [Vec<Cell>]) -> (Vec<Slot>, Vec<Slot>) {
+    let mut across = Vec::new();
+    let mut down = Vec::new();
+    let mut next_number = 1;
+
+    for row in 0..grid.len() {
+        for col in 0..grid[row].len() {
+            let across_length = word_length(grid, row, col, 0, 1);
+            let down_length = word_length(grid, row, col, 1, 0);
+            let starts_across = (!is_letter(grid, row, col.wrapping_sub(1))
+                || is_barred(grid, row, col - 1, 0, 1))
+                && across_length > 1;
+            let starts_down = (!is_letter(grid, row.wrapping_sub(1), col)
+                || is_barred(grid, row - 1, col, 1, 0))
+                && down_length > 1;
+
+            if !starts_across && !starts_down {
+                grid[row][col].number = None;
+                continue;
+            }
+
+            let number = next_number;
+            next_number += 1;
+            grid[row][col].number = Some(number);
+            if starts_across {
+                across.push(Slot {
+                    number,
+                    row,
+                    col,
+                    length: across_length,
+                });
+            }
+            if starts_down {
+                down.push(Slot {
+                    number,
+                    row,
+                    col,
+                    length: down_length,
+                });
+            }
+        }
+    }
+
+    (across, down)
+}
+
+/// Pair numbered slots with clue texts given in the same order.
+pub(crate) fn attach_clues(
+    slots: &[Slot],
+    texts: Vec<String>,
+    direction: &str,
+) -> Result<Vec<Clue>, ParseError> {
+    if slots.len() != texts.len() {
+        return Err(ParseError::InvalidData(format!(
+            "grid has {} {} words but {} {} clues were given",
+            slots.len(),
+            direction,
+            texts.len(),
+            direction
+        )));
+    }
+
+    Ok(slots
+        .iter()
+        .zip(texts)
+        .map(|(slot, text)| Clue {
+            number: slot.number,
+            text,
+            row: slot.row,
+            col: slot.col,
+            length: slot.length,
+            cells: slot.cells(direction_step(direction)),
+            label: None,
+            rich_text: Vec::new(),
+            enumeration: None,
+            references: Vec::new(),
+            continued: Vec::new(),
+        })
+        .collect())
+}
+
+/// Whether (row, col) is inside the grid and holds a letter. Out-of-range
+/// indices (including wrapped negative ones) count as outside the grid.
+fn is_letter(grid: &[Vec<Cell>], row: usize, col: usize) -> bool {
+    grid.get(row)
+        .and_then(|r| r.get(col))
+        .is_some_and(|cell| matches!(cell.kind, CellKind::Letter))
+}
+
+/// Whether a bar separates (row, col) from the next cell in the step direction,
+/// drawn on either side of the shared edge.
+fn is_barred(grid: &[Vec<Cell>], row: usize, col: usize, d_row: usize, d_col: usize) -> bool {
+    let bars = grid[row][col].bars;
+    let next = grid
+        .get(row + d_row)
+        .and_then(|r| r.get(col + d_col))
+        .map(|cell| cell.bars)
+        .unwrap_or_default();
+    if d_row == 1 {
+        bars.bottom || next.top
+    } else {
+        bars.right || next.left
+    }
+}
+
+/// Count consecutive letter cells starting at (row, col) in the given step
+/// direction, stopping at a block or a bar.
+pub(crate) fn word_length(
+    grid: &[Vec<Cell>],
+    row: usize,
+    col: usize,
+    d_row: usize,
+    d_col: usize,
+) -> u8 {
+    let mut length = 0u8;
+    let (mut r, mut c) = (row, col);
+    while is_letter(grid, r, c) {
+        length += 1;
+        if is_barred(grid, r, c, d_row, d_col) {
+            break;
+        }
+        r += d_row;
+        c += d_col;
+    }
+    length
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid_from(rows: &[&str]) -> Vec<Vec<Cell>> {
+        rows.iter()
+            .map(|row| {
+                row.chars()
+                    .map(|c| match c {
+                        '#' => Cell::black(),
+                        '_' => Cell::void(),
+                        _ => Cell::letter(),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_number_grid() {
+        let mut grid = grid_from(&["..#", "...", "#.."]);
+        let (across, down) = number_grid(&mut grid);
+
+        let numbers: Vec<Option<u32>> = grid.iter().flatten().map(|c| c.number).collect();
+        assert_eq!(
+            numbers,
+            vec![
+                Some(1),
+                Some(2),
+                None,
+                Some(3),
+                None,
+                Some(4),
+                None,
+                Some(5),
+                None
+            ]
+        );
+        assert_eq!(
+            across,
+            vec![
+                Slot {
+                    number: 1,
+                    row: 0,
+                    col: 0,
+                    length: 2
+                },
+                Slot {
+                    number: 3,
+                    row: 1,
+                    col: 0,
+                    length: 3
+                },
+                Slot {
+                    number: 5,
+                    row: 2,
+                    col: 1,
+                    length: 2
+                },
+            ]
+        );
+        assert_eq!(
+            down,
+            vec![
+                Slot {
+                    number: 1,
+                    row: 0,
+                    col: 0,
+                    length: 2
+                },
+                Slot {
+                    number: 2,
+                    row: 0,
+                    col: 1,
+                    length: 3
+                },
+                Slot {
+                    number: 4,
+                    row: 1,
+                    col: 2,
+                    length: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_number_grid_with_bars() {
+        let mut grid = grid_from(&["...", "...", "..."]);
+        // A bar between (0, 1) and (0, 2), drawn on the right of (0, 1), and
+        // one between (0, 2) and (1, 2), drawn on the top of (1, 2).
+        grid[0][1].bars.right = true;
+        grid[1][2].bars.top = true;
+        let (across, down) = number_grid(&mut grid);
+
+        let starts: Vec<(u32, usize, usize, u8)> = across
+            .iter()
+            .map(|s| (s.number, s.row, s.col, s.length))
+            .collect();
+        assert_eq!(starts, vec![(1, 0, 0, 2), (3, 1, 0, 3), (5, 2, 0, 3)]);
+        let starts: Vec<(u32, usize, usize, u8)> = down
+            .iter()
+            .map(|s| (s.number, s.row, s.col, s.length))
+            .collect();
+        assert_eq!(starts, vec![(1, 0, 0, 3), (2, 0, 1, 3), (4, 1, 2, 2)]);
+    }
+
+    #[test]
+    fn test_number_grid_with_voids() {
+        // A diamond: the corners lie outside the grid shape.
+        let mut grid = grid_from(&["_._", "...", "_._"]);
+        let (across, down) = number_grid(&mut grid);
+        assert_eq!(
+            across,
+            vec![Slot {
+                number: 2,
+                row: 1,
+                col: 0,
+                length: 3
+            }]
+        );
+        assert_eq!(
+            down,
+            vec![Slot {
+                number: 1,
+                row: 0,
+                col: 1,
+                length: 3
+            }]
+        );
+        assert_eq!(grid[0][0].number, None);
+    }
+
+    #[test]
+    fn test_attach_clues_checks_count() {
+        let mut grid = grid_from(&["..", ".."]);
+        let (across, _) = number_grid(&mut grid);
+        let clues = attach_clues(&across, vec!["One".into(), "Two".into()], "across").unwrap();
+        assert_eq!(clues[1].number, 3);
+        assert_eq!(clues[1].text, "Two");
+        assert!(attach_clues(&across, vec!["One".into()], "across").is_err());
+    }
+}
@@ -0,0 +1,289 @@
+//! Parser for the JSON puzzle documents used by newspaper web apps such as
+//! the New York Times crossword.
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::ParseError;
+use crate::markup::{self, strip_html_tags};
+use crate::types::{Cell, Clue, ClueList, Clues, Puzzle, TextRun};
+
+/// Cell `type` values for circled and shaded squares.
+const TYPE_CIRCLED: u8 = 2;
+const TYPE_SHADED: u8 = 3;
+
+/// Intermediate deserialization types for the puzzle document.
+
+#[derive(Deserialize)]
+struct NytFile {
+    body: Vec<NytBody>,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    constructors: Vec<String>,
+    #[serde(default)]
+    copyright: Option<String>,
+    #[serde(default, rename = "publicationDate")]
+    publication_date: Option<String>,
+    #[serde(default)]
+    notes: Vec<NytNote>,
+}
+
+#[derive(Deserialize)]
+struct NytBody {
+    cells: Vec<NytCell>,
+    #[serde(default)]
+    clues: Vec<NytClue>,
+    dimensions: NytDimensions,
+}
+
+#[derive(Deserialize)]
+struct NytDimensions {
+    width: u8,
+    height: u8,
+}
+
+/// A grid square. Blocks are empty objects.
+#[derive(Deserialize)]
+struct NytCell {
+    #[serde(default)]
+    answer: Option<String>,
+    #[serde(default)]
+    label: Option<String>,
+    #[serde(default, rename = "type")]
+    cell_type: Option<u8>,
+}
+
+#[derive(Deserialize)]
+struct NytClue {
+    cells: Vec<usize>,
+    direction: String,
+    label: String,
+    /// Either a string or a list of `{"plain": ..., "formatted": ...}` parts.
+    text: Value,
+}
+
+#[derive(Deserialize)]
+struct NytNote {
+    text: String,
+}
+
+/// Parse a newspaper puzzle JSON document into a `Puzzle`.
+pub fn parse(data: &[u8]) -> Result<Puzzle, ParseError> {
+    let file: NytFile = serde_json::from_slice(data)?;
+    let body = file
+        .body
+        .into_iter()
+        .next()
+        .ok_or_else(|| ParseError::InvalidData("document has no puzzle body".into()))?;
+
+    let NytDimensions { width, height } = body.dimensions;
+    let (w, h) = (width as usize, height as usize);
+    if w == 0 || h == 0 {
+        return Err(ParseError::InvalidDimensions { width, height });
+    }
+    if body.cells.len() != w * h {
+        return Err(ParseError::InvalidData(format!(
+            "document has {} cells, expected {}",
+            body.cells.len(),
+            w * h
+        )));
+    }
+
+    let grid: Vec<Vec<Cell>> = body
+        .cells
+        .chunks(w)
+        .map(|row| row.iter().map(parse_cell).collect())
+        .collect();
+
+    let mut across = Vec::new();
+    let mut down = Vec::new();
+    let mut other: Vec<ClueList> = Vec::new();
+    for clue in &body.clues {
+        let first = match clue.cells.first() {
+            Some(&first) if clue.cells.iter().all(|&i| i < w * h) => first,
+            _ => {
+                return Err(ParseError::InvalidData(format!(
+                    "clue {} has invalid cells",
+                    clue.label
+                )))
+            }
+        };
+        let number = clue.label.parse().map_err(|_| {
+            ParseError::InvalidData(format!("invalid clue label: {:?}", clue.label))
+        })?;
+        let (text, rich_text) = clue_text(&clue.text);
+        let parsed = Clue {
+            number,
+            text,
+            row: first / w,
+            col: first % w,
+            length: clue.cells.len() as u8,
+            cells: clue.cells.iter().map(|&i| (i / w, i % w)).collect(),
+            label: None,
+            rich_text,
+            enumeration: None,
+            references: Vec::new(),
+            continued: Vec::new(),
+        };
+        match clue.direction.to_lowercase().as_str() {
+            "across" => across.push(parsed),
+            "down" => down.push(parsed),
+            _ => match other.iter_mut().find(|l| l.direction == clue.direction) {
+                Some(list) => list.clues.push(parsed),
+                None => other.push(ClueList {
+                    direction: clue.direction.clone(),
+                    label: clue.direction.clone(),
+                    clues: vec![parsed],
+                }),
+            },
+        }
+    }
+
+    let notes = file
+        .notes
+        .iter()
+        .map(|note| strip_html_tags(&note.text))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Ok(Puzzle {
+        title: file.title.or(file.publication_date).unwrap_or_default(),
+        author: file.constructors.join(", "),
+        copyright: file.copyright.unwrap_or_default(),
+        notes,
+        width,
+        height,
+        grid,
+        clues: Clues {
+            across,
+            down,
+            other,
+        },
+        has_solution: true,
+        is_scrambled: false,
+        timer: None,
+    })
+}
+
+/// Map one document cell to a grid cell. Multi-character answers are rebus squares.
+fn parse_cell(cell: &NytCell) -> Cell {
+    let Some(answer) = cell.answer.as_deref().filter(|a| !a.is_empty()) else {
+        return Cell::black();
+    };
+
+    let answer = answer.to_uppercase();
+    let solution = answer.chars().next().map(String::from);
+    let rebus_solution = (answer.chars().count() > 1).then_some(answer);
+    Cell {
+        number: cell.label.as_deref().and_then(|l| l.parse().ok()),
+        solution,
+        rebus_solution,
+        is_circled: cell.cell_type == Some(TYPE_CIRCLED),
+        is_shaded: cell.cell_type == Some(TYPE_SHADED),
+        ..Cell::letter()
+    }
+}
+
+/// Plain text and formatted runs of a clue, which is either a string or a
+/// list of text parts. A part's `formatted` markup is preferred to its `plain`
+/// text.
+fn clue_text(text: &Value) -> (String, Vec<TextRun>) {
+    match text {
+        Value::String(s) => markup::clue_text(s),
+        Value::Array(parts) => {
+            let markup = parts
+                .iter()
+                .filter_map(|part| match part.get("formatted") {
+                    Some(Value::String(formatted)) => Some(formatted.clone()),
+                    _ => part.get("plain")?.as_str().map(markup::escape),
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            markup::clue_text(&markup)
+        }
+        _ => (String::new(), Vec::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::CellKind;
+
+    const SAMPLE: &str = r#"{
+        "publicationDate": "2024-01-01",
+        "constructors": ["Ann Setter", "Bo Setter"],
+        "copyright": "2024",
+        "notes": [{"text": "A <i>note</i>"}],
+        "body": [{
+            "dimensions": {"width": 3, "height": 2},
+            "cells": [
+                {"answer": "C", "label": "1", "type": 1, "clues": [0]},
+                {"answer": "AT", "label": "2", "type": 1, "clues": [0, 2],
+                 "moreAnswers": {"valid": ["A"]}},
+                {"answer": "T", "type": 2, "clues": [0]},
+                {},
+                {"answer": "O", "label": "3", "type": 3, "clues": [1, 2]},
+                {"answer": "G", "type": 1, "clues": [1]}
+            ],
+            "clues": [
+                {"cells": [0, 1, 2], "direction": "Across", "label": "1",
+                 "text": [{"plain": "Feline friend"}]},
+                {"cells": [4, 5], "direction": "Across", "label": "3",
+                 "text": [{"formatted": "<i>Ego</i> trip?"}]},
+                {"cells": [1, 4], "direction": "Down", "label": "2",
+                 "text": "Hat's dog"}
+            ]
+        }]
+    }"#;
+
+    #[test]
+    fn test_parse_sample() {
+        let puzzle = parse(SAMPLE.as_bytes()).unwrap();
+        assert_eq!(puzzle.title, "2024-01-01");
+        assert_eq!(puzzle.author, "Ann Setter, Bo Setter");
+        assert_eq!(puzzle.notes, "A note");
+        assert_eq!((puzzle.width, puzzle.height), (3, 2));
+
+        assert_eq!(puzzle.grid[0][0].number, Some(1));
+        assert_eq!(puzzle.grid[0][1].rebus_solution.as_deref(), Some("AT"));
+        assert_eq!(puzzle.grid[0][1].solution.as_deref(), Some("A"));
+        assert!(puzzle.grid[0][2].is_circled);
+        assert!(matches!(puzzle.grid[1][0].kind, CellKind::Black));
+        assert!(puzzle.grid[1][1].is_shaded);
+        assert!(!puzzle.grid[1][1].is_circled);
+        assert!(!puzzle.grid[1][2].is_circled);
+
+        assert_eq!(puzzle.clues.across.len(), 2);
+        assert_eq!(puzzle.clues.across[0].text, "Feline friend");
+        assert_eq!(puzzle.clues.across[1].text, "Ego trip?");
+        assert!(puzzle.clues.across[1].rich_text[0].italic);
+        assert!(puzzle.clues.across[0].rich_text.is_empty());
+        assert_eq!(
+            (puzzle.clues.across[1].row, puzzle.clues.across[1].col),
+            (1, 1)
+        );
+        assert_eq!(puzzle.clues.down[0].text, "Hat's dog");
+        assert_eq!(puzzle.clues.down[0].length, 2);
+        assert_eq!(puzzle.clues.down[0].cells, vec![(0, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_documents() {
+        assert!(matches!(
+            parse(br#"{"body": []}"#),
+            Err(ParseError::InvalidData(_))
+        ));
+        let short = SAMPLE.replace(r#""height": 2"#, r#""height": 3"#);
+        assert!(matches!(
+            parse(short.as_bytes()),
+            Err(ParseError::InvalidData(_))
+        ));
+        let outside = SAMPLE.replace("[1, 4]", "[1, 9]");
+        assert!(matches!(
+            parse(outside.as_bytes()),
+            Err(ParseError::InvalidData(_))
+        ));
+    }
+}
@@ -7,43 +7,159 @@ use byteorder::{LittleEndian, ReadBytesExt};
 use std::io::Cursor;
 
 use crate::error::ParseError;
+use crate::numbering::run_cells;
 use crate::types::*;
 
 const MAGIC: &[u8; 12] = b"ACROSS&DOWN\0";
 
-// Header offsets — some are unused now but defined for completeness per the spec.
-#[allow(dead_code)]
+// Header offsets
 const OFFSET_FILE_CHECKSUM: usize = 0x00;
 const OFFSET_MAGIC: usize = 0x02;
-#[allow(dead_code)]
 const OFFSET_HEADER_CHECKSUM: usize = 0x0E;
-#[allow(dead_code)]
 const OFFSET_MASKED_CHECKSUMS: usize = 0x10;
-#[allow(dead_code)]
 const OFFSET_VERSION: usize = 0x18;
-#[allow(dead_code)]
 const OFFSET_SCRAMBLED_CHECKSUM: usize = 0x1E;
 const OFFSET_WIDTH: usize = 0x2C;
 const OFFSET_HEIGHT: usize = 0x2D;
 const OFFSET_NUM_CLUES: usize = 0x2E;
-#[allow(dead_code)]
 const OFFSET_PUZZLE_TYPE: usize = 0x30;
 const OFFSET_SCRAMBLED_TAG: usize = 0x32;
 const HEADER_SIZE: usize = 0x34;
 
+/// Version string written to new files (notes are included in checksums from 1.3 on).
+const VERSION: &[u8; 4] = b"1.3\0";
+const PUZZLE_TYPE_NORMAL: u16 = 0x0001;
+/// Scrambled tag value for a solution locked with a four-digit key.
+const SCRAMBLED_TAG_LOCKED: u16 = 0x0004;
+
+/// Mask XORed with the low/high bytes of the four partial checksums ("ICHEATED").
+const MASK_LOW: &[u8; 4] = b"ICHE";
+const MASK_HIGH: &[u8; 4] = b"ATED";
+
 // Extension section names
 const EXT_GRBS: &[u8; 4] = b"GRBS";
 const EXT_RTBL: &[u8; 4] = b"RTBL";
 const EXT_GEXT: &[u8; 4] = b"GEXT";
 const EXT_LTIM: &[u8; 4] = b"LTIM";
+const EXT_RUSR: &[u8; 4] = b"RUSR";
 
-// GEXT flags
+// GEXT flags. 0x08 is not in the original spec, but some producers use it
+// to mark shaded squares.
+const GEXT_SHADED: u8 = 0x08;
 const GEXT_CIRCLED: u8 = 0x80;
 const GEXT_WAS_INCORRECT: u8 = 0x10;
+const GEXT_INCORRECT: u8 = 0x20;
 const GEXT_REVEALED: u8 = 0x40;
 
+/// How checksum mismatches are handled by `parse_with_options`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChecksumMode {
+    /// Reject the file with `ParseError::ChecksumMismatch` on the first mismatch.
+    Strict,
+    /// Load the file anyway and report every mismatch in `ParsedPuz::checksum_failures`.
+    #[default]
+    Lenient,
+}
+
+/// How to unlock a scrambled solution in `parse_with_options`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unlock {
+    /// Unscramble with a known four-digit key (0000–9999).
+    Key(u16),
+    /// Try every key until one matches the scrambled checksum.
+    BruteForce,
+}
+
+/// Options for `parse_with_options`.
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    pub checksum_mode: ChecksumMode,
+    /// Unscramble a locked solution. Ignored for files that are not scrambled.
+    pub unlock: Option<Unlock>,
+}
+
+/// The part of a .puz file covered by a checksum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChecksumRegion {
+    /// The whole-file checksum at offset 0x00.
+    File,
+    /// The CIB (width, height, clue count, type, scrambled tag) checksum at 0x0E.
+    Header,
+    /// The masked CIB checksum.
+    MaskedHeader,
+    /// The masked solution grid checksum.
+    MaskedSolution,
+    /// The masked player state grid checksum.
+    MaskedGrid,
+    /// The masked string table checksum.
+    MaskedStrings,
+    /// An extension section, by name (e.g. "GEXT").
+    Extension(String),
+}
+
+impl std::fmt::Display for ChecksumRegion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChecksumRegion::File => write!(f, "file"),
+            ChecksumRegion::Header => write!(f, "header"),
+            ChecksumRegion::MaskedHeader => write!(f, "masked header"),
+            ChecksumRegion::MaskedSolution => write!(f, "masked solution"),
+            ChecksumRegion::MaskedGrid => write!(f, "masked grid"),
+            ChecksumRegion::MaskedStrings => write!(f, "masked strings"),
+            ChecksumRegion::Extension(name) => write!(f, "{} section", name),
+        }
+    }
+}
+
+/// A checksum stored in the file that does not match the file's contents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecksumFailure {
+    pub region: ChecksumRegion,
+    /// The checksum stored in the file.
+    pub expected: u16,
+    /// The checksum computed from the data.
+    pub actual: u16,
+}
+
+impl std::fmt::Display for ChecksumFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} checksum mismatch: expected {:#06x}, got {:#06x}",
+            self.region, self.expected, self.actual
+        )
+    }
+}
+
+impl From<ChecksumFailure> for ParseError {
+    fn from(failure: ChecksumFailure) -> Self {
+        ParseError::ChecksumMismatch {
+            region: failure.region.to_string(),
+            expected: failure.expected,
+            actual: failure.actual,
+        }
+    }
+}
+
+/// The result of `parse_with_options`.
+#[derive(Debug, Clone)]
+pub struct ParsedPuz {
+    pub puzzle: Puzzle,
+    /// Checksums that did not match. Always empty in strict mode.
+    pub checksum_failures: Vec<ChecksumFailure>,
+    /// The key that unlocked a scrambled solution, if one was requested.
+    pub unlock_key: Option<u16>,
+}
+
 /// Parse a .puz file from raw bytes.
+///
+/// Checksums are not enforced; use `parse_with_options` to verify them.
 pub fn parse(data: &[u8]) -> Result<Puzzle, ParseError> {
+    parse_with_options(data, &ParseOptions::default()).map(|parsed| parsed.puzzle)
+}
+
+/// Parse a .puz file from raw bytes, verifying its checksums.
+pub fn parse_with_options(data: &[u8], options: &ParseOptions) -> Result<ParsedPuz, ParseError> {
     if data.len() < HEADER_SIZE {
         return Err(ParseError::FileTooShort {
             expected: HEADER_SIZE,
@@ -72,7 +188,7 @@ pub fn parse(data: &[u8]) -> Result<Puzzle, ParseError> {
         let mut cursor = Cursor::new(&data[OFFSET_SCRAMBLED_TAG..]);
         cursor.read_u16::<LittleEndian>().unwrap()
     };
-    let is_scrambled = scrambled_tag != 0;
+    let mut is_scrambled = scrambled_tag != 0;
 
     let grid_size = (width as usize) * (height as usize);
 
@@ -93,6 +209,7 @@ pub fn parse(data: &[u8]) -> Result<Puzzle, ParseError> {
     let state_grid = &data[state_start..state_end];
 
     // Parse null-terminated strings after the grids
+    let raw_strings = split_strings(&data[state_end..], num_clues + 4);
     let strings = parse_strings(&data[state_end..], num_clues + 4)?;
     // strings: [title, author, copyright, clue0, clue1, ..., clueN-1, notes]
     let title = strings.first().cloned().unwrap_or_default();
@@ -110,6 +227,43 @@ pub fn parse(data: &[u8]) -> Result<Puzzle, ParseError> {
         Extensions::default()
     };
 
+    // Verify header, masked and extension checksums
+    let mut checksum_failures = verify_checksums(data, solution_grid, state_grid, &raw_strings);
+    checksum_failures.extend(extensions.checksum_failures.iter().cloned());
+    if options.checksum_mode == ChecksumMode::Strict {
+        if let Some(failure) = checksum_failures.into_iter().next() {
+            return Err(failure.into());
+        }
+        checksum_failures = Vec::new();
+    }
+
+    // Unscramble a locked solution
+    let mut unscrambled_grid = None;
+    let mut unlock_key = None;
+    if let (true, Some(unlock)) = (is_scrambled, options.unlock) {
+        let w = width as usize;
+        let h = height as usize;
+        let scrambled_checksum = u16::from_le_bytes([
+            data[OFFSET_SCRAMBLED_CHECKSUM],
+            data[OFFSET_SCRAMBLED_CHECKSUM + 1],
+        ]);
+        let try_key = |key: u16| {
+            let grid = unscramble_grid(solution_grid, w, h, key);
+            (checksum_region(&column_major_letters(&grid, w, h), 0) == scrambled_checksum)
+                .then_some((key, grid))
+        };
+        let unlocked = match unlock {
+            Unlock::Key(key) if key <= MAX_KEY => try_key(key),
+            Unlock::Key(_) => None,
+            Unlock::BruteForce => (0..=MAX_KEY).find_map(try_key),
+        };
+        let (key, grid) = unlocked.ok_or(ParseError::InvalidKey)?;
+        unscrambled_grid = Some(grid);
+        unlock_key = Some(key);
+        is_scrambled = false;
+    }
+    let solution_grid = unscrambled_grid.as_deref().unwrap_or(solution_grid);
+
     // Build the grid with numbering
     let (grid, across_clues, down_clues) = build_grid(
         width,
@@ -120,7 +274,7 @@ pub fn parse(data: &[u8]) -> Result<Puzzle, ParseError> {
         &extensions,
     )?;
 
-    Ok(Puzzle {
+    let puzzle = Puzzle {
         title,
         author,
         copyright,
@@ -131,43 +285,251 @@ pub fn parse(data: &[u8]) -> Result<Puzzle, ParseError> {
         clues: Clues {
             across: across_clues,
             down: down_clues,
+            other: Vec::new(),
         },
         has_solution: !is_scrambled,
         is_scrambled,
+        timer: extensions.ltim.as_deref().and_then(parse_ltim),
+    };
+
+    Ok(ParsedPuz {
+        puzzle,
+        checksum_failures,
+        unlock_key,
     })
 }
 
-/// Parse null-terminated strings from the data section.
-fn parse_strings(data: &[u8], expected_count: usize) -> Result<Vec<String>, ParseError> {
+/// Compare the header and masked checksums against ones computed from the file body.
+fn verify_checksums(
+    data: &[u8],
+    solution_grid: &[u8],
+    state_grid: &[u8],
+    raw_strings: &[&[u8]],
+) -> Vec<ChecksumFailure> {
+    let notes = if version_checksums_notes(&data[OFFSET_VERSION..OFFSET_VERSION + 4]) {
+        raw_strings.last().copied().unwrap_or_default()
+    } else {
+        &[]
+    };
+    let strings = StringTable {
+        title: raw_strings[0].to_vec(),
+        author: raw_strings[1].to_vec(),
+        copyright: raw_strings[2].to_vec(),
+        clues: raw_strings[3..raw_strings.len() - 1]
+            .iter()
+            .map(|clue| clue.to_vec())
+            .collect(),
+        notes: notes.to_vec(),
+    };
+    let computed = compute_checksums(data, solution_grid, state_grid, &strings);
+
+    let read_u16 = |offset: usize| u16::from_le_bytes([data[offset], data[offset + 1]]);
+    let unmask = |masked: &[u8], i: usize| {
+        u16::from_le_bytes([masked[i] ^ MASK_LOW[i], masked[i + 4] ^ MASK_HIGH[i]])
+    };
+    let stored_masked = &data[OFFSET_MASKED_CHECKSUMS..OFFSET_MASKED_CHECKSUMS + 8];
+
+    let checks = [
+        (
+            ChecksumRegion::File,
+            read_u16(OFFSET_FILE_CHECKSUM),
+            computed.file,
+        ),
+        (
+            ChecksumRegion::Header,
+            read_u16(OFFSET_HEADER_CHECKSUM),
+            computed.cib,
+        ),
+        (
+            ChecksumRegion::MaskedHeader,
+            unmask(stored_masked, 0),
+            unmask(&computed.masked, 0),
+        ),
+        (
+            ChecksumRegion::MaskedSolution,
+            unmask(stored_masked, 1),
+            unmask(&computed.masked, 1),
+        ),
+        (
+            ChecksumRegion::MaskedGrid,
+            unmask(stored_masked, 2),
+            unmask(&computed.masked, 2),
+        ),
+        (
+            ChecksumRegion::MaskedStrings,
+            unmask(stored_masked, 3),
+            unmask(&computed.masked, 3),
+        ),
+    ];
+
+    checks
+        .into_iter()
+        .filter(|(_, expected, actual)| expected != actual)
+        .map(|(region, expected, actual)| ChecksumFailure {
+            region,
+            expected,
+            actual,
+        })
+        .collect()
+}
+
+/// The largest four-digit scrambling key.
+const MAX_KEY: u16 = 9999;
+
+/// The four decimal digits of a scrambling key, most significant first.
+fn key_digits(key: u16) -> [u8; 4] {
+    [
+        (key / 1000 % 10) as u8,
+        (key / 100 % 10) as u8,
+        (key / 10 % 10) as u8,
+        (key % 10) as u8,
+    ]
+}
+
+/// The letters of a solution grid read column by column, skipping black squares.
+/// Scrambling and the scrambled checksum both operate on this sequence.
+fn column_major_letters(grid: &[u8], w: usize, h: usize) -> Vec<u8> {
+    (0..w)
+        .flat_map(|col| (0..h).map(move |row| grid[row * w + col]))
+        .filter(|&b| b != b'.')
+        .collect()
+}
+
+/// Put column-major letters back into a grid, leaving black squares in place.
+fn restore_column_major(grid: &[u8], w: usize, h: usize, letters: &[u8]) -> Vec<u8> {
+    let mut restored = grid.to_vec();
+    let mut letters = letters.iter();
+    for col in 0..w {
+        for row in 0..h {
+            let idx = row * w + col;
+            if grid[idx] != b'.' {
+                if let Some(&letter) = letters.next() {
+                    restored[idx] = letter;
+                }
+            }
+        }
+    }
+    restored
+}
+
+/// Unscramble a locked solution grid with the given key.
+fn unscramble_grid(grid: &[u8], w: usize, h: usize, key: u16) -> Vec<u8> {
+    let letters = unscramble_letters(&column_major_letters(grid, w, h), key);
+    restore_column_major(grid, w, h, &letters)
+}
+
+/// Scramble a solution grid with the given key.
+fn scramble_grid(grid: &[u8], w: usize, h: usize, key: u16) -> Vec<u8> {
+    let letters = scramble_letters(&column_major_letters(grid, w, h), key);
+    restore_column_major(grid, w, h, &letters)
+}
+
+/// The Across Lite scrambling algorithm: for each key digit, shift every
+/// letter by the key digits, rotate the sequence left by the digit, then
+/// interleave its two halves.
+fn scramble_letters(letters: &[u8], key: u16) -> Vec<u8> {
+    let digits = key_digits(key);
+    let mut s = letters.to_vec();
+    for &k in &digits {
+        shift_letters(&mut s, &digits, 1);
+        if (k as usize) < s.len() {
+            s.rotate_left(k as usize);
+        }
+        s = shuffle(&s);
+    }
+    s
+}
+
+/// Riffle shuffle: alternate letters from the second and first halves; an
+/// odd final letter stays last.
+fn shuffle(s: &[u8]) -> Vec<u8> {
+    let mid = s.len() / 2;
+    let mut shuffled: Vec<u8> = (0..mid).flat_map(|i| [s[mid + i], s[i]]).collect();
+    if s.len() % 2 == 1 {
+        shuffled.push(s[s.len() - 1]);
+    }
+    shuffled
+}
+
+/// Reverse `scramble_letters`: for each key digit in reverse order, undo the
+/// interleave, the rotation and the digit shift.
+fn unscramble_letters(letters: &[u8], key: u16) -> Vec<u8> {
+    let digits = key_digits(key);
+    let mut s = letters.to_vec();
+    for &k in digits.iter().rev() {
+        s = unshuffle(&s);
+        if (k as usize) < s.len() {
+            s.rotate_right(k as usize);
+        }
+        shift_letters(&mut s, &digits, -1);
+    }
+    s
+}
+
+/// Shift each letter by the key digit at its position (cycling through the
+/// four digits), wrapping around the alphabet.
+fn shift_letters(s: &mut [u8], digits: &[u8; 4], direction: i16) {
+    for (i, c) in s.iter_mut().enumerate() {
+        if c.is_ascii_uppercase() {
+            let offset = (*c - b'A') as i16 + direction * digits[i % 4] as i16;
+            *c = b'A' + offset.rem_euclid(26) as u8;
+        }
+    }
+}
+
+/// Undo the riffle shuffle: odd positions came from the first half, even
+/// positions from the second.
+fn unshuffle(s: &[u8]) -> Vec<u8> {
+    s.iter()
+        .skip(1)
+        .step_by(2)
+        .chain(s.iter().step_by(2))
+        .copied()
+        .collect()
+}
+
+/// Whether a header version string ("1.2", "1.3", ...) includes notes in the checksums.
+fn version_checksums_notes(version: &[u8]) -> bool {
+    match version {
+        [major @ b'0'..=b'9', b'.', minor @ b'0'..=b'9', ..] => (*major, *minor) >= (b'1', b'3'),
+        _ => false,
+    }
+}
+
+/// Split the data section into `expected_count` null-terminated strings.
+/// Missing strings at the end of a truncated file are returned as empty.
+fn split_strings(data: &[u8], expected_count: usize) -> Vec<&[u8]> {
     let mut strings = Vec::with_capacity(expected_count);
     let mut pos = 0;
 
     for _ in 0..expected_count {
         match data[pos..].iter().position(|&b| b == 0) {
             Some(end) => {
-                let s = decode_string(&data[pos..pos + end]);
-                strings.push(s);
+                strings.push(&data[pos..pos + end]);
                 pos += end + 1;
             }
             None => {
-                // Last string may not be null-terminated
-                if pos < data.len() {
-                    let s = decode_string(&data[pos..]);
-                    strings.push(s);
-                    break;
-                } else {
-                    // Pad with empty strings if we've run out of data
-                    strings.push(String::new());
-                }
+                // Last string may not be null-terminated; pad with empty
+                // strings once we've run out of data
+                strings.push(&data[pos..]);
+                pos = data.len();
             }
         }
     }
 
-    Ok(strings)
+    strings
+}
+
+/// Parse null-terminated strings from the data section.
+fn parse_strings(data: &[u8], expected_count: usize) -> Result<Vec<String>, ParseError> {
+    Ok(split_strings(data, expected_count)
+        .into_iter()
+        .map(decode_string)
+        .collect())
 }
 
 /// Decode bytes to string, trying UTF-8 first, then falling back to ISO-8859-1.
-fn decode_string(bytes: &[u8]) -> String {
+pub(crate) fn decode_string(bytes: &[u8]) -> String {
     match std::str::from_utf8(bytes) {
         Ok(s) => s.to_string(),
         Err(_) => {
@@ -203,8 +565,12 @@ struct Extensions {
     rtbl: std::collections::HashMap<u8, String>,
     /// GEXT: grid of extra flags (circled, revealed, was_incorrect)
     gext: Vec<u8>,
-    /// LTIM: timer state "elapsed,is_running"
+    /// LTIM: timer state "elapsed,stopped"
     ltim: Option<String>,
+    /// RUSR: per-cell player rebus entries (empty = none)
+    rusr: Vec<String>,
+    /// Sections whose stored checksum did not match their data
+    checksum_failures: Vec<ChecksumFailure>,
 }
 
 /// Parse extension sections from the data after the strings.
@@ -221,7 +587,7 @@ fn parse_extensions(data: &[u8]) -> Extensions {
                 Err(_) => break,
             }
         };
-        // Skip checksum at pos+6..pos+8
+        let stored_checksum = u16::from_le_bytes([data[pos + 6], data[pos + 7]]);
         let section_data_start = pos + 8;
         let section_data_end = section_data_start + length;
 
@@ -231,6 +597,15 @@ fn parse_extensions(data: &[u8]) -> Extensions {
 
         let section_data = &data[section_data_start..section_data_end];
 
+        let computed_checksum = checksum_region(section_data, 0);
+        if computed_checksum != stored_checksum {
+            ext.checksum_failures.push(ChecksumFailure {
+                region: ChecksumRegion::Extension(decode_string(name)),
+                expected: stored_checksum,
+                actual: computed_checksum,
+            });
+        }
+
         if name == EXT_GRBS {
             ext.grbs = section_data.to_vec();
         } else if name == EXT_RTBL {
@@ -239,6 +614,8 @@ fn parse_extensions(data: &[u8]) -> Extensions {
             ext.gext = section_data.to_vec();
         } else if name == EXT_LTIM {
             ext.ltim = Some(decode_string(section_data));
+        } else if name == EXT_RUSR {
+            ext.rusr = section_data.split(|&b| b == 0).map(decode_string).collect();
         }
 
         // Skip past section data + null terminator
@@ -269,6 +646,368 @@ fn parse_rtbl(data: &[u8]) -> std::collections::HashMap<u8, String> {
     map
 }
 
+/// Serialize a `Puzzle` to the .puz (Across Lite) binary format.
+///
+/// Clues are written in the order Across Lite expects: by grid position, with
+/// the across clue before the down clue for cells that start both. Rebus,
+/// circle and revealed/incorrect flags are written to the GRBS/RTBL and GEXT
+/// extension sections when any cell uses them, multi-letter player entries to
+/// RUSR, and the timer to LTIM.
+pub fn write(puzzle: &Puzzle) -> Result<Vec<u8>, ParseError> {
+    write_puz(puzzle, None)
+}
+
+/// Serialize a `Puzzle` to a locked .puz file whose solution is scrambled
+/// with a four-digit key (0000–9999).
+///
+/// The header records the scrambled tag and a checksum of the unscrambled
+/// solution, so solvers can verify a completed grid, and the file unlocks
+/// with `Unlock::Key`. Every solution square must be a letter A–Z.
+pub fn write_locked(puzzle: &Puzzle, key: u16) -> Result<Vec<u8>, ParseError> {
+    if key > MAX_KEY {
+        return Err(ParseError::InvalidKey);
+    }
+    write_puz(puzzle, Some(key))
+}
+
+fn write_puz(puzzle: &Puzzle, scramble_key: Option<u16>) -> Result<Vec<u8>, ParseError> {
+    let w = puzzle.width as usize;
+    let h = puzzle.height as usize;
+    if w == 0 || h == 0 {
+        return Err(ParseError::InvalidDimensions {
+            width: puzzle.width,
+            height: puzzle.height,
+        });
+    }
+    if puzzle.grid.len() != h || puzzle.grid.iter().any(|row| row.len() != w) {
+        return Err(ParseError::InvalidData(format!(
+            "grid does not match puzzle dimensions {}x{}",
+            w, h
+        )));
+    }
+    if puzzle.is_scrambled {
+        return Err(ParseError::InvalidData(
+            "cannot write a scrambled puzzle".into(),
+        ));
+    }
+
+    // Solution and player state grids
+    let mut solution_grid = Vec::with_capacity(w * h);
+    let mut state_grid = Vec::with_capacity(w * h);
+    for cell in puzzle.grid.iter().flatten() {
+        match cell.kind {
+            // .puz has no void squares, so they are written as blocks
+            CellKind::Black | CellKind::Void => {
+                solution_grid.push(b'.');
+                state_grid.push(b'.');
+            }
+            CellKind::Letter => {
+                let solution = cell.solution.as_deref().or(cell.rebus_solution.as_deref());
+                solution_grid.push(solution.and_then(encode_char).unwrap_or(b'-'));
+                state_grid.push(
+                    cell.player_value
+                        .as_deref()
+                        .and_then(encode_char)
+                        .unwrap_or(b'-'),
+                );
+            }
+        }
+    }
+
+    let strings = StringTable {
+        title: encode_string(&puzzle.title),
+        author: encode_string(&puzzle.author),
+        copyright: encode_string(&puzzle.copyright),
+        clues: ordered_clue_texts(puzzle, &solution_grid)
+            .iter()
+            .map(|text| encode_string(text))
+            .collect(),
+        notes: encode_string(&puzzle.notes),
+    };
+
+    let num_clues = u16::try_from(strings.clues.len())
+        .map_err(|_| ParseError::InvalidData("too many clues".into()))?;
+
+    let mut scrambled_checksum = 0;
+    if let Some(key) = scramble_key {
+        if solution_grid
+            .iter()
+            .any(|&b| b != b'.' && !b.is_ascii_uppercase())
+        {
+            return Err(ParseError::InvalidData(
+                "only A-Z solutions can be scrambled".into(),
+            ));
+        }
+        scrambled_checksum = checksum_region(&column_major_letters(&solution_grid, w, h), 0);
+        solution_grid = scramble_grid(&solution_grid, w, h, key);
+    }
+
+    // Header
+    let mut data = vec![0u8; HEADER_SIZE];
+    data[OFFSET_MAGIC..OFFSET_MAGIC + 12].copy_from_slice(MAGIC);
+    data[OFFSET_VERSION..OFFSET_VERSION + 4].copy_from_slice(VERSION);
+    data[OFFSET_WIDTH] = puzzle.width;
+    data[OFFSET_HEIGHT] = puzzle.height;
+    put_u16(&mut data, OFFSET_NUM_CLUES, num_clues);
+    put_u16(&mut data, OFFSET_PUZZLE_TYPE, PUZZLE_TYPE_NORMAL);
+    if scramble_key.is_some() {
+        put_u16(&mut data, OFFSET_SCRAMBLED_CHECKSUM, scrambled_checksum);
+        put_u16(&mut data, OFFSET_SCRAMBLED_TAG, SCRAMBLED_TAG_LOCKED);
+    }
+
+    let checksums = compute_checksums(&data, &solution_grid, &state_grid, &strings);
+    put_u16(&mut data, OFFSET_FILE_CHECKSUM, checksums.file);
+    put_u16(&mut data, OFFSET_HEADER_CHECKSUM, checksums.cib);
+    data[OFFSET_MASKED_CHECKSUMS..OFFSET_MASKED_CHECKSUMS + 8].copy_from_slice(&checksums.masked);
+
+    // Grids and strings
+    data.extend_from_slice(&solution_grid);
+    data.extend_from_slice(&state_grid);
+    for s in [&strings.title, &strings.author, &strings.copyright] {
+        data.extend_from_slice(s);
+        data.push(0);
+    }
+    for clue in &strings.clues {
+        data.extend_from_slice(clue);
+        data.push(0);
+    }
+    data.extend_from_slice(&strings.notes);
+    data.push(0);
+
+    write_extensions(&mut data, puzzle)?;
+
+    Ok(data)
+}
+
+/// Clue texts in .puz string-table order, matched to the numbering that
+/// `build_grid` derives from the solution grid.
+fn ordered_clue_texts<'a>(puzzle: &'a Puzzle, solution_grid: &[u8]) -> Vec<&'a str> {
+    let w = puzzle.width as usize;
+    let h = puzzle.height as usize;
+    let find_text = |clues: &'a [Clue], row: usize, col: usize| {
+        clues
+            .iter()
+            .find(|c| c.row == row && c.col == col)
+            .map(|c| c.text.as_str())
+            .unwrap_or("")
+    };
+
+    let mut texts = Vec::new();
+    for row in 0..h {
+        for col in 0..w {
+            if is_across_start(solution_grid, w, h, row, col) {
+                texts.push(find_text(&puzzle.clues.across, row, col));
+            }
+            if is_down_start(solution_grid, w, h, row, col) {
+                texts.push(find_text(&puzzle.clues.down, row, col));
+            }
+        }
+    }
+    texts
+}
+
+/// Append the GRBS/RTBL, GEXT, RUSR and LTIM extension sections for any rebus
+/// or flagged cells, rebus entries and the saved timer.
+///
+/// GRBS holds one byte per cell, so at most 255 distinct rebus answers fit.
+fn write_extensions(data: &mut Vec<u8>, puzzle: &Puzzle) -> Result<(), ParseError> {
+    let cells: Vec<&Cell> = puzzle.grid.iter().flatten().collect();
+
+    // Rebus: GRBS holds 1 + the RTBL key for each rebus cell
+    let mut rebus_table: Vec<&str> = Vec::new();
+    let mut grbs = vec![0u8; cells.len()];
+    for (idx, cell) in cells.iter().enumerate() {
+        if let Some(rebus) = cell.rebus_solution.as_deref() {
+            let key = match rebus_table.iter().position(|r| *r == rebus) {
+                Some(key) => key,
+                None => {
+                    rebus_table.push(rebus);
+                    rebus_table.len() - 1
+                }
+            };
+            grbs[idx] = u8::try_from(key + 1)
+                .map_err(|_| ParseError::InvalidData("too many distinct rebus answers".into()))?;
+        }
+    }
+    if !rebus_table.is_empty() {
+        let rtbl: String = rebus_table
+            .iter()
+            .enumerate()
+            .map(|(key, rebus)| format!("{:>2}:{};", key, rebus))
+            .collect();
+        write_section(data, EXT_GRBS, &grbs);
+        write_section(data, EXT_RTBL, &encode_string(&rtbl));
+    }
+
+    let gext: Vec<u8> = cells
+        .iter()
+        .map(|cell| {
+            let mut flags = 0;
+            if cell.is_circled {
+                flags |= GEXT_CIRCLED;
+            }
+            if cell.was_incorrect {
+                flags |= GEXT_WAS_INCORRECT;
+            }
+            if cell.is_incorrect {
+                flags |= GEXT_INCORRECT;
+            }
+            if cell.is_revealed {
+                flags |= GEXT_REVEALED;
+            }
+            if cell.is_shaded {
+                flags |= GEXT_SHADED;
+            }
+            flags
+        })
+        .collect();
+    if gext.iter().any(|&flags| flags != 0) {
+        write_section(data, EXT_GEXT, &gext);
+    }
+
+    // RUSR: the player's multi-letter entries, one null-terminated string per cell
+    let has_rebus_entries = cells.iter().any(|cell| {
+        cell.player_value
+            .as_ref()
+            .is_some_and(|v| v.chars().count() > 1)
+    });
+    if has_rebus_entries {
+        let mut rusr = Vec::new();
+        for cell in &cells {
+            if let Some(value) = cell.player_value.as_ref().filter(|v| v.chars().count() > 1) {
+                rusr.extend_from_slice(&encode_string(value));
+            }
+            rusr.push(0);
+        }
+        write_section(data, EXT_RUSR, &rusr);
+    }
+
+    if let Some(timer) = puzzle.timer {
+        let ltim = format!(
+            "{},{}",
+            timer.elapsed_seconds,
+            if timer.is_running { 0 } else { 1 }
+        );
+        write_section(data, EXT_LTIM, ltim.as_bytes());
+    }
+    Ok(())
+}
+
+/// Append one extension section: name, length, checksum, data, null terminator.
+fn write_section(data: &mut Vec<u8>, name: &[u8; 4], section_data: &[u8]) {
+    data.extend_from_slice(name);
+    data.extend_from_slice(&(section_data.len() as u16).to_le_bytes());
+    data.extend_from_slice(&checksum_region(section_data, 0).to_le_bytes());
+    data.extend_from_slice(section_data);
+    data.push(0);
+}
+
+fn put_u16(data: &mut [u8], offset: usize, value: u16) {
+    data[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+}
+
+/// Encode a string as Windows-1252, the encoding Across Lite expects.
+fn encode_string(s: &str) -> Vec<u8> {
+    let (bytes, _, _) = encoding_rs::WINDOWS_1252.encode(s);
+    bytes.into_owned()
+}
+
+/// Encode the first character of a cell value as a single grid byte.
+fn encode_char(s: &str) -> Option<u8> {
+    let c = s.chars().next()?;
+    let bytes = encode_string(&c.to_uppercase().to_string());
+    match bytes.as_slice() {
+        [b] => Some(*b),
+        _ => None,
+    }
+}
+
+/// The encoded string section of a .puz file, as covered by its checksums.
+struct StringTable {
+    title: Vec<u8>,
+    author: Vec<u8>,
+    copyright: Vec<u8>,
+    clues: Vec<Vec<u8>>,
+    notes: Vec<u8>,
+}
+
+impl StringTable {
+    /// Checksum of the string section. Title, author, copyright and notes are
+    /// included with their null terminator only when non-empty; clues are
+    /// included without it.
+    fn checksum(&self, seed: u16) -> u16 {
+        let mut cksum = seed;
+        for s in [&self.title, &self.author, &self.copyright] {
+            if !s.is_empty() {
+                cksum = checksum_region(s, cksum);
+                cksum = checksum_region(&[0], cksum);
+            }
+        }
+        for clue in &self.clues {
+            cksum = checksum_region(clue, cksum);
+        }
+        if !self.notes.is_empty() {
+            cksum = checksum_region(&self.notes, cksum);
+            cksum = checksum_region(&[0], cksum);
+        }
+        cksum
+    }
+}
+
+/// The header checksums of a .puz file.
+struct Checksums {
+    /// Whole-file checksum at `OFFSET_FILE_CHECKSUM`.
+    file: u16,
+    /// Checksum of the 8-byte CIB block starting at `OFFSET_WIDTH`.
+    cib: u16,
+    /// The four partial checksums masked with "ICHEATED" (low bytes, then high bytes).
+    masked: [u8; 8],
+}
+
+/// Compute the file, CIB and masked checksums from a header and the file body.
+fn compute_checksums(
+    header: &[u8],
+    solution_grid: &[u8],
+    state_grid: &[u8],
+    strings: &StringTable,
+) -> Checksums {
+    let cib = checksum_region(&header[OFFSET_WIDTH..OFFSET_WIDTH + 8], 0);
+
+    let mut file = checksum_region(solution_grid, cib);
+    file = checksum_region(state_grid, file);
+    file = strings.checksum(file);
+
+    let partials = [
+        cib,
+        checksum_region(solution_grid, 0),
+        checksum_region(state_grid, 0),
+        strings.checksum(0),
+    ];
+    let mut masked = [0u8; 8];
+    for (i, partial) in partials.iter().enumerate() {
+        masked[i] = MASK_LOW[i] ^ (partial & 0xFF) as u8;
+        masked[i + 4] = MASK_HIGH[i] ^ (partial >> 8) as u8;
+    }
+
+    Checksums { file, cib, masked }
+}
+
+/// The Across Lite CRC-16 variant: rotate right by one, then add the next byte.
+fn checksum_region(data: &[u8], seed: u16) -> u16 {
+    data.iter().fold(seed, |cksum, &b| {
+        cksum.rotate_right(1).wrapping_add(b as u16)
+    })
+}
+
+/// Parse the LTIM timer string: elapsed seconds, then 0 if running or 1 if stopped.
+fn parse_ltim(s: &str) -> Option<Timer> {
+    let (elapsed, state) = s.split_once(',')?;
+    Some(Timer {
+        elapsed_seconds: elapsed.trim().parse().ok()?,
+        is_running: state.trim() == "0",
+    })
+}
+
 type BuildGridResult = (Vec<Vec<Cell>>, Vec<Clue>, Vec<Clue>);
 
 /// Build the puzzle grid with clue numbering.
@@ -298,16 +1037,7 @@ fn build_grid(
             let is_black = sol_byte == b'.';
 
             if is_black {
-                grid_row.push(Cell {
-                    kind: CellKind::Black,
-                    number: None,
-                    solution: None,
-                    rebus_solution: None,
-                    player_value: None,
-                    is_circled: false,
-                    was_incorrect: false,
-                    is_revealed: false,
-                });
+                grid_row.push(Cell::black());
                 continue;
             }
 
@@ -330,6 +1060,12 @@ fn build_grid(
                         row,
                         col,
                         length,
+                        cells: run_cells(row, col, length, 0, 1),
+                        label: None,
+                        rich_text: Vec::new(),
+                        enumeration: None,
+                        references: Vec::new(),
+                        continued: Vec::new(),
                     });
                 }
 
@@ -344,6 +1080,12 @@ fn build_grid(
                         row,
                         col,
                         length,
+                        cells: run_cells(row, col, length, 1, 0),
+                        label: None,
+                        rich_text: Vec::new(),
+                        enumeration: None,
+                        references: Vec::new(),
+                        continued: Vec::new(),
                     });
                 }
 
@@ -371,28 +1113,34 @@ fn build_grid(
                 None
             };
 
-            // Player value from state grid
-            let player_value = if state_byte != b'-' && state_byte != b'.' && state_byte != 0 {
-                Some((state_byte as char).to_uppercase().to_string())
-            } else {
-                None
+            // Player value from the RUSR rebus entry, else the state grid
+            let player_value = match extensions.rusr.get(idx) {
+                Some(entry) if !entry.is_empty() => Some(entry.to_uppercase()),
+                _ if state_byte != b'-' && state_byte != b'.' && state_byte != 0 => {
+                    Some((state_byte as char).to_uppercase().to_string())
+                }
+                _ => None,
             };
 
             // GEXT flags
             let gext_byte = extensions.gext.get(idx).copied().unwrap_or(0);
             let is_circled = gext_byte & GEXT_CIRCLED != 0;
             let was_incorrect = gext_byte & GEXT_WAS_INCORRECT != 0;
+            let is_incorrect = gext_byte & GEXT_INCORRECT != 0;
             let is_revealed = gext_byte & GEXT_REVEALED != 0;
+            let is_shaded = gext_byte & GEXT_SHADED != 0;
 
             grid_row.push(Cell {
-                kind: CellKind::Letter,
                 number: cell_number,
                 solution,
                 rebus_solution,
                 player_value,
                 is_circled,
+                is_shaded,
                 was_incorrect,
+                is_incorrect,
                 is_revealed,
+                ..Cell::letter()
             });
         }
         grid.push(grid_row);
@@ -569,4 +1317,412 @@ mod tests {
         let data = vec![0u8; 10];
         assert!(parse(&data).is_err());
     }
+
+    #[test]
+    fn test_write_round_trip() {
+        let original = parse(&make_test_puz()).expect("should parse");
+        let written = write(&original).expect("should write");
+        let puzzle = parse(&written).expect("should parse written file");
+
+        assert_eq!(puzzle.title, "Test Puzzle");
+        assert_eq!(puzzle.author, "Test Author");
+        assert_eq!(puzzle.copyright, "2024");
+        assert_eq!(puzzle.width, 3);
+        assert_eq!(puzzle.height, 3);
+        assert!(matches!(puzzle.grid[1][0].kind, CellKind::Black));
+        assert_eq!(puzzle.grid[2][2].solution.as_deref(), Some("G"));
+        assert_eq!(puzzle.clues.across[0].text, "Feline friend");
+        assert_eq!(puzzle.clues.down[0].text, "Letter between N and P");
+        assert_eq!(puzzle.clues.across[1].text, "Canine friend");
+    }
+
+    #[test]
+    fn test_write_checksums_match_fixture() {
+        let data = include_bytes!("../tests/fixtures/puzzleme-example-crossword.puz");
+        let puzzle = parse(data).expect("should parse fixture");
+        let written = write(&puzzle).expect("should write");
+
+        assert_eq!(
+            written[OFFSET_FILE_CHECKSUM..OFFSET_FILE_CHECKSUM + 2],
+            data[0..2]
+        );
+        assert_eq!(
+            written[OFFSET_HEADER_CHECKSUM..OFFSET_HEADER_CHECKSUM + 2],
+            data[OFFSET_HEADER_CHECKSUM..OFFSET_HEADER_CHECKSUM + 2]
+        );
+        assert_eq!(
+            written[OFFSET_MASKED_CHECKSUMS..OFFSET_MASKED_CHECKSUMS + 8],
+            data[OFFSET_MASKED_CHECKSUMS..OFFSET_MASKED_CHECKSUMS + 8]
+        );
+        assert_eq!(written[HEADER_SIZE..], data[HEADER_SIZE..]);
+    }
+
+    #[test]
+    fn test_write_player_state_and_extensions() {
+        let mut original = parse(&make_test_puz()).expect("should parse");
+        original.grid[0][0].rebus_solution = Some("CAT".into());
+        original.grid[2][0].rebus_solution = Some("CAT".into());
+        original.grid[2][2].rebus_solution = Some("GOD".into());
+        original.grid[0][1].is_circled = true;
+        original.grid[2][0].is_shaded = true;
+        original.grid[1][1].is_revealed = true;
+        original.grid[2][1].was_incorrect = true;
+        original.grid[2][2].is_incorrect = true;
+        original.grid[0][2].player_value = Some("T".into());
+
+        let written = write(&original).expect("should write");
+        let puzzle = parse(&written).expect("should parse written file");
+
+        assert_eq!(puzzle.grid[0][0].rebus_solution.as_deref(), Some("CAT"));
+        assert_eq!(puzzle.grid[2][0].rebus_solution.as_deref(), Some("CAT"));
+        assert_eq!(puzzle.grid[2][2].rebus_solution.as_deref(), Some("GOD"));
+        assert!(puzzle.grid[0][1].rebus_solution.is_none());
+        assert!(puzzle.grid[0][1].is_circled);
+        assert!(puzzle.grid[2][0].is_shaded);
+        assert!(!puzzle.grid[2][0].is_circled);
+        assert!(puzzle.grid[1][1].is_revealed);
+        assert!(puzzle.grid[2][1].was_incorrect);
+        assert!(!puzzle.grid[2][1].is_incorrect);
+        assert!(puzzle.grid[2][2].is_incorrect);
+        assert!(!puzzle.grid[2][2].was_incorrect);
+        assert!(!puzzle.grid[0][0].is_circled);
+        assert_eq!(puzzle.grid[0][2].player_value.as_deref(), Some("T"));
+        assert!(puzzle.grid[0][0].player_value.is_none());
+    }
+
+    #[test]
+    fn test_parse_rusr_player_rebus() {
+        let mut data = make_test_puz();
+        // State grid: player entered C in the first cell
+        data[HEADER_SIZE + 9] = b'C';
+        let rusr = b"cat\0\0\0\0\0\0\0\0DOG\0";
+        data.extend_from_slice(EXT_RUSR);
+        data.extend_from_slice(&(rusr.len() as u16).to_le_bytes());
+        data.extend_from_slice(&checksum_region(rusr, 0).to_le_bytes());
+        data.extend_from_slice(rusr);
+        data.push(0);
+
+        let puzzle = parse(&data).expect("should parse");
+        assert_eq!(puzzle.grid[0][0].player_value.as_deref(), Some("CAT"));
+        assert_eq!(puzzle.grid[2][2].player_value.as_deref(), Some("DOG"));
+        assert!(puzzle.grid[0][1].player_value.is_none());
+    }
+
+    #[test]
+    fn test_write_rusr_round_trip() {
+        let mut original = parse(&make_test_puz()).expect("should parse");
+        original.grid[0][0].player_value = Some("CAT".into());
+        original.grid[0][1].player_value = Some("A".into());
+
+        let written = write(&original).expect("should write");
+        // The state grid holds the first letter of the rebus entry
+        assert_eq!(written[HEADER_SIZE + 9], b'C');
+
+        let puzzle = parse(&written).expect("should parse written file");
+        assert_eq!(puzzle.grid[0][0].player_value.as_deref(), Some("CAT"));
+        assert_eq!(puzzle.grid[0][1].player_value.as_deref(), Some("A"));
+        assert!(puzzle.grid[0][2].player_value.is_none());
+    }
+
+    #[test]
+    fn test_parse_ltim() {
+        let running = parse_ltim("754,0").expect("should parse");
+        assert_eq!(running.elapsed_seconds, 754);
+        assert!(running.is_running);
+        let stopped = parse_ltim("12,1").expect("should parse");
+        assert_eq!(stopped.elapsed_seconds, 12);
+        assert!(!stopped.is_running);
+        assert!(parse_ltim("garbage").is_none());
+    }
+
+    #[test]
+    fn test_write_timer_round_trip() {
+        let mut original = parse(&make_test_puz()).expect("should parse");
+        assert!(original.timer.is_none());
+        original.timer = Some(Timer {
+            elapsed_seconds: 1234,
+            is_running: false,
+        });
+
+        let written = write(&original).expect("should write");
+        let parsed = parse_with_options(
+            &written,
+            &ParseOptions {
+                checksum_mode: ChecksumMode::Strict,
+                ..ParseOptions::default()
+            },
+        )
+        .expect("should parse written file");
+        assert_eq!(parsed.puzzle.timer, original.timer);
+    }
+
+    #[test]
+    fn test_write_rejects_mismatched_grid() {
+        let mut puzzle = parse(&make_test_puz()).expect("should parse");
+        puzzle.grid.pop();
+        assert!(matches!(write(&puzzle), Err(ParseError::InvalidData(_))));
+    }
+
+    #[test]
+    fn test_write_rejects_too_many_rebus_answers() {
+        let mut puzzle = parse(&make_test_puz()).expect("should parse");
+        let cell = Cell {
+            solution: Some("A".into()),
+            ..Cell::letter()
+        };
+        puzzle.grid = vec![vec![cell; 16]; 16];
+        (puzzle.width, puzzle.height) = (16, 16);
+        for (idx, cell) in puzzle.grid.iter_mut().flatten().enumerate() {
+            cell.rebus_solution = Some(format!("A{}", idx % 255));
+        }
+        assert!(write(&puzzle).is_ok());
+
+        puzzle.grid[15][15].rebus_solution = Some("A255".into());
+        assert!(matches!(write(&puzzle), Err(ParseError::InvalidData(_))));
+    }
+
+    #[test]
+    fn test_checksum_region() {
+        assert_eq!(checksum_region(&[], 0), 0);
+        assert_eq!(checksum_region(&[1], 0), 1);
+        // An odd checksum rotates its low bit into the high bit before adding.
+        assert_eq!(checksum_region(&[0], 1), 0x8000);
+        assert_eq!(checksum_region(&[2, 3], 0), 4);
+    }
+
+    fn strict() -> ParseOptions {
+        ParseOptions {
+            checksum_mode: ChecksumMode::Strict,
+            ..ParseOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_fixture_checksums_verify() {
+        let data = include_bytes!("../tests/fixtures/puzzleme-example-crossword.puz");
+        let parsed = parse_with_options(data, &ParseOptions::default()).expect("should parse");
+        assert!(parsed.checksum_failures.is_empty());
+        assert!(parse_with_options(data, &strict()).is_ok());
+    }
+
+    #[test]
+    fn test_written_file_checksums_verify() {
+        let mut puzzle = parse(&make_test_puz()).expect("should parse");
+        puzzle.notes = "Some notes".into();
+        puzzle.grid[0][0].is_circled = true;
+        puzzle.grid[0][1].rebus_solution = Some("AB".into());
+        let written = write(&puzzle).expect("should write");
+        let parsed = parse_with_options(&written, &strict()).expect("should verify");
+        assert_eq!(parsed.puzzle.notes, "Some notes");
+    }
+
+    #[test]
+    fn test_corrupt_clue_fails_checksums() {
+        let data = include_bytes!("../tests/fixtures/puzzleme-example-crossword.puz");
+        let mut corrupt = data.to_vec();
+        let pos = corrupt.len() - 3;
+        corrupt[pos] = b'X';
+
+        let parsed = parse_with_options(&corrupt, &ParseOptions::default()).expect("lenient");
+        let regions: Vec<ChecksumRegion> = parsed
+            .checksum_failures
+            .iter()
+            .map(|f| f.region.clone())
+            .collect();
+        assert_eq!(
+            regions,
+            vec![ChecksumRegion::File, ChecksumRegion::MaskedStrings]
+        );
+
+        let err = parse_with_options(&corrupt, &strict()).unwrap_err();
+        assert!(matches!(err, ParseError::ChecksumMismatch { ref region, .. } if region == "file"));
+    }
+
+    #[test]
+    fn test_truncated_file_fails_checksums() {
+        let data = include_bytes!("../tests/fixtures/puzzleme-example-crossword.puz");
+        let truncated = &data[..data.len() - 40];
+
+        let parsed = parse_with_options(truncated, &ParseOptions::default()).expect("lenient");
+        assert!(!parsed.checksum_failures.is_empty());
+        assert_eq!(
+            parsed.puzzle.clues.across.len() + parsed.puzzle.clues.down.len(),
+            10
+        );
+        assert!(parse_with_options(truncated, &strict()).is_err());
+    }
+
+    #[test]
+    fn test_corrupt_extension_fails_checksum() {
+        let mut puzzle = parse(&make_test_puz()).expect("should parse");
+        puzzle.grid[0][0].is_circled = true;
+        let mut written = write(&puzzle).expect("should write");
+        // GEXT data is the last section: flip the flags of the final cell
+        let pos = written.len() - 2;
+        written[pos] = GEXT_CIRCLED;
+
+        let parsed = parse_with_options(&written, &ParseOptions::default()).expect("lenient");
+        assert_eq!(parsed.checksum_failures.len(), 1);
+        assert_eq!(
+            parsed.checksum_failures[0].region,
+            ChecksumRegion::Extension("GEXT".into())
+        );
+        assert!(parse_with_options(&written, &strict()).is_err());
+    }
+
+    #[test]
+    fn test_unchecksummed_file_loads_leniently() {
+        let data = include_bytes!("../tests/fixtures/all-a.puz");
+        assert!(parse(data).is_ok());
+        assert!(parse_with_options(data, &strict()).is_err());
+    }
+
+    /// The 5x5 fixture with its solution scrambled under key 1234 (generated
+    /// with the reference Across Lite scrambling algorithm).
+    fn make_scrambled_puz() -> Vec<u8> {
+        let mut data = include_bytes!("../tests/fixtures/puzzleme-example-crossword.puz").to_vec();
+        data[HEADER_SIZE..HEADER_SIZE + 25].copy_from_slice(b".DAJGZXAWEBQYBBMNKIOWAON.");
+        put_u16(&mut data, OFFSET_SCRAMBLED_TAG, 0x0004);
+        put_u16(&mut data, OFFSET_SCRAMBLED_CHECKSUM, 0x0274);
+        data
+    }
+
+    fn unlock_with(unlock: Unlock) -> ParseOptions {
+        ParseOptions {
+            unlock: Some(unlock),
+            ..ParseOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_scrambled_puzzle_stays_locked_without_key() {
+        let puzzle = parse(&make_scrambled_puz()).expect("should parse");
+        assert!(puzzle.is_scrambled);
+        assert!(!puzzle.has_solution);
+        assert_eq!(puzzle.grid[0][1].solution.as_deref(), Some("D"));
+    }
+
+    #[test]
+    fn test_unlock_with_key() {
+        let parsed = parse_with_options(&make_scrambled_puz(), &unlock_with(Unlock::Key(1234)))
+            .expect("should unlock");
+        assert_eq!(parsed.unlock_key, Some(1234));
+        let puzzle = parsed.puzzle;
+        assert!(!puzzle.is_scrambled);
+        assert!(puzzle.has_solution);
+        let row: String = puzzle.grid[1]
+            .iter()
+            .filter_map(|cell| cell.solution.clone())
+            .collect();
+        assert_eq!(row, "STOVE");
+    }
+
+    #[test]
+    fn test_unlock_rejects_wrong_key() {
+        let data = make_scrambled_puz();
+        let err = parse_with_options(&data, &unlock_with(Unlock::Key(4321))).unwrap_err();
+        assert!(matches!(err, ParseError::InvalidKey));
+        let err = parse_with_options(&data, &unlock_with(Unlock::Key(12345))).unwrap_err();
+        assert!(matches!(err, ParseError::InvalidKey));
+    }
+
+    #[test]
+    fn test_unlock_brute_force() {
+        let parsed = parse_with_options(&make_scrambled_puz(), &unlock_with(Unlock::BruteForce))
+            .expect("should recover key");
+        let key = parsed.unlock_key.expect("key should be reported");
+        // Any key that satisfies the checksum must reproduce the real solution
+        let solution: String = parsed
+            .puzzle
+            .grid
+            .iter()
+            .flatten()
+            .map(|cell| cell.solution.as_deref().unwrap_or("."))
+            .collect();
+        assert_eq!(solution, ".SNOWSTOVEEATENAGENTMESS.", "key {key}");
+    }
+
+    #[test]
+    fn test_unlock_ignored_for_unscrambled_puzzle() {
+        let parsed = parse_with_options(&make_test_puz(), &unlock_with(Unlock::Key(1234)))
+            .expect("should parse");
+        assert_eq!(parsed.unlock_key, None);
+        assert_eq!(parsed.puzzle.grid[0][0].solution.as_deref(), Some("C"));
+    }
+
+    #[test]
+    fn test_unshuffle() {
+        assert_eq!(unshuffle(b"DAEBFC"), b"ABCDEF");
+        assert_eq!(unshuffle(b"CADBE"), b"ABCDE");
+    }
+
+    #[test]
+    fn test_shuffle_round_trip() {
+        assert_eq!(shuffle(b"ABCDEF"), b"DAEBFC");
+        assert_eq!(shuffle(b"ABCDE"), b"CADBE");
+        for key in [0, 1234, 7090, 9999] {
+            let letters = b"THEQUICKBROWNFOX";
+            assert_eq!(
+                unscramble_letters(&scramble_letters(letters, key), key),
+                letters
+            );
+        }
+    }
+
+    #[test]
+    fn test_write_locked_matches_reference() {
+        let data = include_bytes!("../tests/fixtures/puzzleme-example-crossword.puz");
+        let puzzle = parse(data).expect("should parse");
+        let locked = write_locked(&puzzle, 1234).expect("should write");
+        let reference = make_scrambled_puz();
+
+        assert_eq!(
+            locked[HEADER_SIZE..HEADER_SIZE + 25],
+            reference[HEADER_SIZE..HEADER_SIZE + 25]
+        );
+        assert_eq!(
+            locked[OFFSET_SCRAMBLED_CHECKSUM..OFFSET_SCRAMBLED_CHECKSUM + 2],
+            reference[OFFSET_SCRAMBLED_CHECKSUM..OFFSET_SCRAMBLED_CHECKSUM + 2]
+        );
+        assert_eq!(
+            locked[OFFSET_SCRAMBLED_TAG..OFFSET_SCRAMBLED_TAG + 2],
+            [0x04, 0x00]
+        );
+    }
+
+    #[test]
+    fn test_write_locked_round_trip() {
+        let data = include_bytes!("../tests/fixtures/puzzleme-example-crossword.puz");
+        let puzzle = parse(data).expect("should parse");
+        let locked = write_locked(&puzzle, 7090).expect("should write");
+
+        let still_locked = parse_with_options(&locked, &strict()).expect("checksums verify");
+        assert!(still_locked.puzzle.is_scrambled);
+
+        let options = ParseOptions {
+            checksum_mode: ChecksumMode::Strict,
+            unlock: Some(Unlock::Key(7090)),
+        };
+        let unlocked = parse_with_options(&locked, &options).expect("should unlock");
+        assert!(unlocked.puzzle.has_solution);
+        for (row, cells) in puzzle.grid.iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                assert_eq!(unlocked.puzzle.grid[row][col].solution, cell.solution);
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_locked_rejects_invalid_input() {
+        let mut puzzle = parse(&make_test_puz()).expect("should parse");
+        assert!(matches!(
+            write_locked(&puzzle, 10000),
+            Err(ParseError::InvalidKey)
+        ));
+        puzzle.grid[0][0].solution = Some("7".into());
+        assert!(matches!(
+            write_locked(&puzzle, 1234),
+            Err(ParseError::InvalidData(_))
+        ));
+    }
 }
@@ -0,0 +1,296 @@
+//! Parser for PuzzleMe (Amuse Labs) puzzle data.
+//!
+//! PuzzleMe pages embed the puzzle as a base64 `rawc` blob that decodes to
+//! JSON. Either the blob or the decoded JSON can be parsed.
+
+use base64::engine::general_purpose::STANDARD_NO_PAD;
+use base64::Engine;
+use serde::Deserialize;
+
+use crate::error::ParseError;
+use crate::markup::{clue_text, strip_html_tags};
+use crate::numbering::run_cells;
+use crate::types::{parse_color, Cell, Clue, Clues, Puzzle};
+
+/// Value of a `box` entry for a block.
+const BLOCK: &str = "\u{0}";
+
+/// Intermediate deserialization types for the decoded `rawc` JSON.
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PuzzleMeFile {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    author: String,
+    #[serde(default)]
+    copyright: String,
+    #[serde(default)]
+    description: String,
+    w: u8,
+    h: u8,
+    /// Solutions indexed `[column][row]`.
+    #[serde(rename = "box")]
+    boxes: Vec<Vec<Option<String>>>,
+    #[serde(default)]
+    cell_infos: Vec<PuzzleMeCellInfo>,
+    #[serde(default)]
+    placed_words: Vec<PuzzleMeWord>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PuzzleMeCellInfo {
+    x: usize,
+    y: usize,
+    #[serde(default)]
+    is_circled: bool,
+    #[serde(default)]
+    is_void: bool,
+    #[serde(default)]
+    bg_color: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PuzzleMeWord {
+    clue: PuzzleMeClue,
+    clue_num: u32,
+    across_not_down: bool,
+    x: usize,
+    y: usize,
+    n_boxes: u8,
+}
+
+#[derive(Deserialize)]
+struct PuzzleMeClue {
+    clue: String,
+}
+
+/// Parse a PuzzleMe `rawc` blob, or its decoded JSON, into a `Puzzle`.
+pub fn parse(data: &[u8]) -> Result<Puzzle, ParseError> {
+    let json = decode(data)?;
+    let file: PuzzleMeFile = serde_json::from_slice(&json)?;
+
+    let (w, h) = (file.w as usize, file.h as usize);
+    if w == 0 || h == 0 {
+        return Err(ParseError::InvalidDimensions {
+            width: file.w,
+            height: file.h,
+        });
+    }
+    if file.boxes.len() != w || file.boxes.iter().any(|column| column.len() != h) {
+        return Err(ParseError::InvalidData(format!(
+            "box does not match the {}x{} grid",
+            w, h
+        )));
+    }
+
+    let mut grid: Vec<Vec<Cell>> = (0..h)
+        .map(|row| {
+            (0..w)
+                .map(|col| parse_box(file.boxes[col][row].as_deref()))
+                .collect()
+        })
+        .collect();
+
+    for info in &file.cell_infos {
+        let Some(cell) = grid.get_mut(info.y).and_then(|r| r.get_mut(info.x)) else {
+            continue;
+        };
+        if info.is_void {
+            *cell = Cell::void();
+        } else {
+            cell.is_circled = info.is_circled;
+            cell.background_color = info.bg_color.as_deref().and_then(parse_color);
+        }
+    }
+
+    let mut across = Vec::new();
+    let mut down = Vec::new();
+    for word in &file.placed_words {
+        let cell = grid
+            .get_mut(word.y)
+            .and_then(|r| r.get_mut(word.x))
+            .ok_or_else(|| {
+                ParseError::InvalidData(format!(
+                    "clue {} starts outside the grid at ({}, {})",
+                    word.clue_num, word.y, word.x
+                ))
+            })?;
+        cell.number = Some(word.clue_num);
+
+        let (text, rich_text) = clue_text(&word.clue.clue);
+        let clue = Clue {
+            number: word.clue_num,
+            text,
+            row: word.y,
+            col: word.x,
+            length: word.n_boxes,
+            cells: if word.across_not_down {
+                run_cells(word.y, word.x, word.n_boxes, 0, 1)
+            } else {
+                run_cells(word.y, word.x, word.n_boxes, 1, 0)
+            },
+            label: None,
+            rich_text,
+            enumeration: None,
+            references: Vec::new(),
+            continued: Vec::new(),
+        };
+        if word.across_not_down {
+            across.push(clue);
+        } else {
+            down.push(clue);
+        }
+    }
+    across.sort_by_key(|c| c.number);
+    down.sort_by_key(|c| c.number);
+
+    Ok(Puzzle {
+        title: file.title,
+        author: file.author,
+        copyright: file.copyright,
+        notes: strip_html_tags(&file.description),
+        width: file.w,
+        height: file.h,
+        grid,
+        clues: Clues {
+            across,
+            down,
+            other: Vec::new(),
+        },
+        has_solution: true,
+        is_scrambled: false,
+        timer: None,
+    })
+}
+
+/// Decode a `rawc` blob to JSON. Data that is already JSON is returned as is.
+fn decode(data: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let text = std::str::from_utf8(data)
+        .map_err(|e| ParseError::Encoding(e.to_string()))?
+        .trim_start_matches('\u{feff}')
+        .trim();
+    if text.starts_with('{') {
+        return Ok(text.as_bytes().to_vec());
+    }
+
+    let blob: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    STANDARD_NO_PAD
+        .decode(blob.trim_end_matches('='))
+        .map_err(|e| ParseError::Encoding(format!("invalid rawc blob: {}", e)))
+}
+
+/// Map one `box` entry to a cell. Multi-letter entries are rebus squares.
+fn parse_box(value: Option<&str>) -> Cell {
+    let value = value.unwrap_or(BLOCK).trim();
+    if value.is_empty() || value == BLOCK {
+        return Cell::black();
+    }
+
+    let answer = value.to_uppercase();
+    let solution = answer.chars().next().map(String::from);
+    if answer.chars().count() > 1 {
+        Cell {
+            solution,
+            rebus_solution: Some(answer),
+            ..Cell::letter()
+        }
+    } else {
+        Cell {
+            solution,
+            ..Cell::letter()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::CellKind;
+
+    const SAMPLE: &str = r##"{
+        "title": "Mini",
+        "author": "Setter",
+        "copyright": "2024 Amuse Labs",
+        "description": "<b>Notes</b> here",
+        "w": 3,
+        "h": 2,
+        "box": [["C", "\u0000"], ["AT", "O"], ["T", "G"]],
+        "cellInfos": [{"x": 2, "y": 1, "isCircled": true, "bgColor": "#C0FFEE"}],
+        "placedWords": [
+            {"clue": {"clue": "Feline <i>friend</i>"}, "clueNum": 1,
+             "acrossNotDown": true, "x": 0, "y": 0, "nBoxes": 3},
+            {"clue": {"clue": "Dog's home"}, "clueNum": 3,
+             "acrossNotDown": true, "x": 1, "y": 1, "nBoxes": 2},
+            {"clue": {"clue": "Hat's dog"}, "clueNum": 2,
+             "acrossNotDown": false, "x": 1, "y": 0, "nBoxes": 2}
+        ]
+    }"##;
+
+    fn encode(json: &str) -> String {
+        base64::engine::general_purpose::STANDARD.encode(json)
+    }
+
+    #[test]
+    fn test_parse_rawc_blob() {
+        let puzzle = parse(encode(SAMPLE).as_bytes()).unwrap();
+        assert_eq!(puzzle.title, "Mini");
+        assert_eq!(puzzle.author, "Setter");
+        assert_eq!(puzzle.notes, "Notes here");
+        assert_eq!((puzzle.width, puzzle.height), (3, 2));
+
+        assert_eq!(puzzle.grid[0][0].solution.as_deref(), Some("C"));
+        assert!(matches!(puzzle.grid[1][0].kind, CellKind::Black));
+        assert_eq!(puzzle.grid[0][1].rebus_solution.as_deref(), Some("AT"));
+        assert_eq!(puzzle.grid[0][1].solution.as_deref(), Some("A"));
+        assert!(puzzle.grid[1][2].is_circled);
+        assert_eq!(
+            puzzle.grid[1][2].background_color.as_deref(),
+            Some("#C0FFEE")
+        );
+        assert_eq!(puzzle.grid[0][1].number, Some(2));
+
+        assert_eq!(puzzle.clues.across.len(), 2);
+        assert_eq!(puzzle.clues.across[0].text, "Feline friend");
+        assert!(puzzle.clues.across[0].rich_text[1].italic);
+        assert_eq!(puzzle.clues.down[0].number, 2);
+        assert_eq!(puzzle.clues.down[0].length, 2);
+    }
+
+    #[test]
+    fn test_parse_decoded_json() {
+        let puzzle = parse(SAMPLE.as_bytes()).unwrap();
+        assert_eq!(puzzle.clues.across[1].number, 3);
+        assert_eq!(
+            (puzzle.clues.across[1].row, puzzle.clues.across[1].col),
+            (1, 1)
+        );
+    }
+
+    #[test]
+    fn test_parse_blob_with_line_breaks() {
+        let blob = encode(SAMPLE);
+        let wrapped: Vec<&str> = blob
+            .as_bytes()
+            .chunks(76)
+            .map(|chunk| std::str::from_utf8(chunk).unwrap())
+            .collect();
+        assert!(parse(wrapped.join("\n").as_bytes()).is_ok());
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_input() {
+        assert!(matches!(
+            parse(b"not base64!"),
+            Err(ParseError::Encoding(_))
+        ));
+        let mismatched = SAMPLE.replace(r#""w": 3"#, r#""w": 4"#);
+        assert!(matches!(
+            parse(mismatched.as_bytes()),
+            Err(ParseError::InvalidData(_))
+        ));
+    }
+}
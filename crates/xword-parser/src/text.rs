@@ -0,0 +1,10 @@
+//! Helpers shared by the writers of the plain-text formats.
+
+/// Grid symbols assigned to rebus answers when writing, in order. None of
+/// them is a letter or a block in Across Lite text or xd grids.
+pub(crate) const REBUS_SYMBOLS: &str = "1234567890@$%&*+?!=~^";
+
+/// Collapse line breaks so a value fits on one line.
+pub(crate) fn single_line(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
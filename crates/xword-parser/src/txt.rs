@@ -0,0 +1,514 @@
+//! Parser and writer for the Across Lite text format (.txt).
+//!
+//! File format reference:
+//! https://www.litsoft.com/across/docs/AcrossTextFormat.pdf
+
+use std::collections::HashMap;
+
+use crate::error::ParseError;
+use crate::numbering::{attach_clues, number_grid, number_grid_without_bars, Slot};
+use crate::puz::decode_string;
+use crate::text::{single_line, REBUS_SYMBOLS};
+use crate::types::{Cell, CellKind, Clue, Clues, Puzzle};
+
+const HEADER_V1: &str = "<ACROSS PUZZLE>";
+const HEADER_V2: &str = "<ACROSS PUZZLE V2>";
+
+/// Flag in the <REBUS> section: lowercase grid letters are circled.
+const REBUS_MARK: &str = "MARK;";
+
+/// A rebus table entry: the full answer and the single-letter short form.
+struct RebusEntry {
+    answer: String,
+    short: String,
+}
+
+/// Parse an Across Lite text file into a `Puzzle`.
+pub fn parse(data: &[u8]) -> Result<Puzzle, ParseError> {
+    let text = decode_string(data);
+    let mut lines = text.lines().map(str::trim).skip_while(|l| l.is_empty());
+
+    let header = lines.next().unwrap_or("");
+    if header != HEADER_V1 && header != HEADER_V2 {
+        return Err(ParseError::InvalidData(
+            "missing <ACROSS PUZZLE> header".into(),
+        ));
+    }
+
+    // Group lines by section tag. Blank lines only matter inside <NOTEPAD>.
+    let mut sections: HashMap<String, Vec<&str>> = HashMap::new();
+    let mut current: Option<String> = None;
+    for line in lines {
+        if line.starts_with('<') && line.ends_with('>') {
+            let tag = line[1..line.len() - 1].to_uppercase();
+            sections.entry(tag.clone()).or_default();
+            current = Some(tag);
+        } else if let Some(tag) = &current {
+            if !line.is_empty() || tag == "NOTEPAD" {
+                sections.entry(tag.clone()).or_default().push(line);
+            }
+        } else if !line.is_empty() {
+            return Err(ParseError::InvalidData(format!(
+                "text outside of a section: {}",
+                line
+            )));
+        }
+    }
+
+    let single = |tag: &str| -> String {
+        sections
+            .get(tag)
+            .map(|lines| lines.join(" "))
+            .unwrap_or_default()
+    };
+
+    let (width, height) = parse_size(&single("SIZE"))?;
+    let (rebus_table, mark_circles) =
+        parse_rebus(sections.get("REBUS").map(Vec::as_slice).unwrap_or(&[]))?;
+
+    let grid_lines = sections
+        .get("GRID")
+        .ok_or_else(|| ParseError::InvalidData("missing <GRID> section".into()))?;
+    if grid_lines.len() != height as usize {
+        return Err(ParseError::InvalidData(format!(
+            "grid has {} rows, expected {}",
+            grid_lines.len(),
+            height
+        )));
+    }
+
+    let mut grid: Vec<Vec<Cell>> = Vec::with_capacity(height as usize);
+    for (row, line) in grid_lines.iter().enumerate() {
+        let chars: Vec<char> = line.chars().collect();
+        if chars.len() != width as usize {
+            return Err(ParseError::InvalidData(format!(
+                "row {} has {} cells, expected {}",
+                row,
+                chars.len(),
+                width
+            )));
+        }
+        grid.push(
+            chars
+                .into_iter()
+                .map(|c| parse_grid_char(c, &rebus_table, mark_circles))
+                .collect::<Result<_, _>>()?,
+        );
+    }
+
+    let (across_slots, down_slots) = number_grid(&mut grid);
+    let clue_lines = |tag: &str| -> Vec<String> {
+        sections
+            .get(tag)
+            .map(|lines| lines.iter().map(|l| l.to_string()).collect())
+            .unwrap_or_default()
+    };
+    let across = attach_clues(&across_slots, clue_lines("ACROSS"), "across")?;
+    let down = attach_clues(&down_slots, clue_lines("DOWN"), "down")?;
+
+    let notes = sections
+        .get("NOTEPAD")
+        .map(|lines| lines.join("\n").trim().to_string())
+        .unwrap_or_default();
+
+    Ok(Puzzle {
+        title: single("TITLE"),
+        author: single("AUTHOR"),
+        copyright: single("COPYRIGHT"),
+        notes,
+        width,
+        height,
+        grid,
+        clues: Clues {
+            across,
+            down,
+            other: Vec::new(),
+        },
+        has_solution: true,
+        is_scrambled: false,
+        timer: None,
+    })
+}
+
+/// Parse a <SIZE> value such as "15x15" (columns x rows).
+fn parse_size(size: &str) -> Result<(u8, u8), ParseError> {
+    let invalid = || ParseError::InvalidData(format!("invalid <SIZE>: {:?}", size));
+    let size = size.to_lowercase();
+    let (w, h) = size.split_once('x').ok_or_else(invalid)?;
+    let width: u8 = w.trim().parse().map_err(|_| invalid())?;
+    let height: u8 = h.trim().parse().map_err(|_| invalid())?;
+    if width == 0 || height == 0 {
+        return Err(ParseError::InvalidDimensions { width, height });
+    }
+    Ok((width, height))
+}
+
+/// Parse the <REBUS> section: "MARK;" and lines like "1:HEART:H".
+/// Returns the rebus table keyed by grid symbol, and whether MARK is set.
+fn parse_rebus(lines: &[&str]) -> Result<(HashMap<char, RebusEntry>, bool), ParseError> {
+    let mut table = HashMap::new();
+    let mut mark_circles = false;
+
+    for line in lines {
+        if line.eq_ignore_ascii_case(REBUS_MARK) {
+            mark_circles = true;
+            continue;
+        }
+        let parts: Vec<&str> = line.splitn(3, ':').collect();
+        let [symbol, answer, short] = parts.as_slice() else {
+            return Err(ParseError::InvalidData(format!(
+                "invalid <REBUS> entry: {}",
+                line
+            )));
+        };
+        let mut symbol_chars = symbol.trim().chars();
+        let (Some(symbol), None) = (symbol_chars.next(), symbol_chars.next()) else {
+            return Err(ParseError::InvalidData(format!(
+                "invalid <REBUS> symbol: {}",
+                line
+            )));
+        };
+        table.insert(
+            symbol,
+            RebusEntry {
+                answer: answer.trim().to_uppercase(),
+                short: short.trim().to_uppercase(),
+            },
+        );
+    }
+
+    Ok((table, mark_circles))
+}
+
+/// Map one <GRID> character to a cell.
+fn parse_grid_char(
+    c: char,
+    rebus_table: &HashMap<char, RebusEntry>,
+    mark_circles: bool,
+) -> Result<Cell, ParseError> {
+    if c == '.' {
+        return Ok(Cell::black());
+    }
+    if let Some(entry) = rebus_table.get(&c) {
+        let short = entry
+            .short
+            .chars()
+            .next()
+            .or_else(|| entry.answer.chars().next())
+            .map(String::from);
+        return Ok(Cell {
+            solution: short,
+            rebus_solution: Some(entry.answer.clone()),
+            ..Cell::letter()
+        });
+    }
+    if c.is_alphabetic() {
+        return Ok(Cell {
+            solution: Some(c.to_uppercase().to_string()),
+            is_circled: mark_circles && c.is_lowercase(),
+            ..Cell::letter()
+        });
+    }
+    Err(ParseError::InvalidData(format!(
+        "invalid grid character: {:?}",
+        c
+    )))
+}
+
+/// Serialize a `Puzzle` to Across Lite V2 text.
+///
+/// Circled letters are written in lowercase with the <REBUS> `MARK;` flag, and
+/// rebus squares (or non-letter solutions) get a symbol from the rebus table.
+/// Clues are written in the order the grid numbering expects. The output is
+/// UTF-8, with every section line indented by a tab.
+pub fn write(puzzle: &Puzzle) -> Result<Vec<u8>, ParseError> {
+    let mut rebus_entries: Vec<(char, String, char)> = Vec::new();
+    let mut symbols = REBUS_SYMBOLS.chars();
+    let mut has_circles = false;
+    let mut grid_lines = Vec::with_capacity(puzzle.grid.len());
+
+    for row in &puzzle.grid {
+        let mut line = String::with_capacity(row.len());
+        for cell in row {
+            // Text format has no void squares, so they are written as blocks
+            if !matches!(cell.kind, CellKind::Letter) {
+                line.push('.');
+                continue;
+            }
+            let solution = cell
+                .rebus_solution
+                .as_deref()
+                .or(cell.solution.as_deref())
+                .ok_or_else(|| {
+                    ParseError::InvalidData("every square needs a solution in text format".into())
+                })?
+                .to_uppercase();
+
+            let mut chars = solution.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_alphabetic() => {
+                    if cell.is_circled {
+                        has_circles = true;
+                        line.extend(c.to_lowercase());
+                    } else {
+                        line.push(c);
+                    }
+                }
+                _ => {
+                    let existing = rebus_entries
+                        .iter()
+                        .find(|(_, answer, _)| *answer == solution);
+                    let symbol = match existing {
+                        Some((symbol, _, _)) => *symbol,
+                        None => {
+                            let symbol = symbols.next().ok_or_else(|| {
+                                ParseError::InvalidData("too many distinct rebus answers".into())
+                            })?;
+                            let short = cell
+                                .solution
+                                .as_deref()
+                                .and_then(|s| s.chars().next())
+                                .or_else(|| solution.chars().next())
+                                .unwrap_or('X');
+                            rebus_entries.push((symbol, solution, short));
+                            symbol
+                        }
+                    };
+                    line.push(symbol);
+                }
+            }
+        }
+        grid_lines.push(line);
+    }
+
+    // Bars cannot be written, so clues follow the grid as it will be read back
+    let (across_slots, down_slots) = number_grid_without_bars(&puzzle.grid);
+
+    let mut out = String::new();
+    let mut section = |tag: &str, lines: &[String]| {
+        out.push_str(&format!("<{}>\n", tag));
+        for line in lines {
+            out.push_str(&format!("\t{}\n", line));
+        }
+    };
+
+    section("ACROSS PUZZLE V2", &[]);
+    section("TITLE", &[single_line(&puzzle.title)]);
+    section("AUTHOR", &[single_line(&puzzle.author)]);
+    section("COPYRIGHT", &[single_line(&puzzle.copyright)]);
+    section("SIZE", &[format!("{}x{}", puzzle.width, puzzle.height)]);
+    section("GRID", &grid_lines);
+    if !rebus_entries.is_empty() || has_circles {
+        let mut lines = Vec::new();
+        if has_circles {
+            lines.push(REBUS_MARK.to_string());
+        }
+        for (symbol, answer, short) in &rebus_entries {
+            lines.push(format!("{}:{}:{}", symbol, answer, short));
+        }
+        section("REBUS", &lines);
+    }
+    section("ACROSS", &slot_texts(&across_slots, &puzzle.clues.across));
+    section("DOWN", &slot_texts(&down_slots, &puzzle.clues.down));
+    if !puzzle.notes.is_empty() {
+        let lines: Vec<String> = puzzle.notes.lines().map(str::to_string).collect();
+        section("NOTEPAD", &lines);
+    }
+
+    Ok(out.into_bytes())
+}
+
+/// Clue texts for each numbered slot, matched by starting cell.
+fn slot_texts(slots: &[Slot], clues: &[Clue]) -> Vec<String> {
+    slots
+        .iter()
+        .map(|slot| {
+            clues
+                .iter()
+                .find(|c| c.row == slot.row && c.col == slot.col)
+                .map(|c| single_line(&c.text))
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "<ACROSS PUZZLE V2>
+<TITLE>
+\tTest Puzzle
+<AUTHOR>
+\tTest Author
+<COPYRIGHT>
+\t2024 Test
+<SIZE>
+\t3x3
+<GRID>
+\tC1T
+\t.o.
+\tDOG
+<REBUS>
+\tMARK;
+\t1:HEART:A
+<ACROSS>
+\tFeline friend
+\tCanine friend
+<DOWN>
+\tTicker
+<NOTEPAD>
+\tFirst line
+
+\tSecond line
+";
+
+    #[test]
+    fn test_parse_basic_txt() {
+        let puzzle = parse(SAMPLE.as_bytes()).unwrap();
+
+        assert_eq!(puzzle.title, "Test Puzzle");
+        assert_eq!(puzzle.author, "Test Author");
+        assert_eq!(puzzle.copyright, "2024 Test");
+        assert_eq!(puzzle.notes, "First line\n\nSecond line");
+        assert_eq!(puzzle.width, 3);
+        assert_eq!(puzzle.height, 3);
+        assert!(puzzle.has_solution);
+
+        assert!(matches!(puzzle.grid[1][0].kind, CellKind::Black));
+        assert_eq!(puzzle.grid[0][0].solution, Some("C".to_string()));
+        assert_eq!(puzzle.grid[0][0].number, Some(1));
+        assert_eq!(puzzle.grid[0][1].number, Some(2));
+        assert_eq!(puzzle.grid[2][0].number, Some(3));
+
+        assert_eq!(puzzle.clues.across.len(), 2);
+        assert_eq!(puzzle.clues.across[1].number, 3);
+        assert_eq!(puzzle.clues.across[1].text, "Canine friend");
+        assert_eq!(puzzle.clues.down.len(), 1);
+        assert_eq!(puzzle.clues.down[0].number, 2);
+        assert_eq!(puzzle.clues.down[0].length, 3);
+    }
+
+    #[test]
+    fn test_parse_rebus_and_marks() {
+        let puzzle = parse(SAMPLE.as_bytes()).unwrap();
+
+        assert_eq!(puzzle.grid[0][1].rebus_solution, Some("HEART".to_string()));
+        assert_eq!(puzzle.grid[0][1].solution, Some("A".to_string()));
+        assert!(puzzle.grid[1][1].is_circled);
+        assert_eq!(puzzle.grid[1][1].solution, Some("O".to_string()));
+        assert!(!puzzle.grid[2][1].is_circled);
+    }
+
+    #[test]
+    fn test_parse_v1_without_optional_sections() {
+        let text = "<ACROSS PUZZLE>\n<TITLE>\nT\n<SIZE>\n2x2\n<GRID>\nAB\nCD\n<ACROSS>\na1\na3\n<DOWN>\nd1\nd2\n";
+        let puzzle = parse(text.as_bytes()).unwrap();
+        assert_eq!(puzzle.title, "T");
+        assert_eq!(puzzle.author, "");
+        assert_eq!(puzzle.notes, "");
+        assert_eq!(puzzle.clues.down[1].text, "d2");
+        assert_eq!(puzzle.clues.down[1].col, 1);
+    }
+
+    #[test]
+    fn test_reject_missing_header() {
+        let err = parse(b"<TITLE>\nNope\n").unwrap_err();
+        assert!(matches!(err, ParseError::InvalidData(_)));
+    }
+
+    #[test]
+    fn test_reject_clue_count_mismatch() {
+        let text = SAMPLE.replace("\tTicker\n", "");
+        let err = parse(text.as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("down"));
+    }
+
+    #[test]
+    fn test_reject_bad_grid() {
+        let text = SAMPLE.replace("\tDOG\n", "\tDO\n");
+        assert!(parse(text.as_bytes()).is_err());
+        let text = SAMPLE.replace("<SIZE>\n\t3x3", "<SIZE>\n\t3 by 3");
+        assert!(parse(text.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_write_exact_output() {
+        let mut puzzle = parse(SAMPLE.as_bytes()).unwrap();
+        puzzle.grid[2][2].is_circled = true;
+
+        let written = String::from_utf8(write(&puzzle).unwrap()).unwrap();
+        assert_eq!(
+            written,
+            "<ACROSS PUZZLE V2>
+<TITLE>
+\tTest Puzzle
+<AUTHOR>
+\tTest Author
+<COPYRIGHT>
+\t2024 Test
+<SIZE>
+\t3x3
+<GRID>
+\tC1T
+\t.o.
+\tDOg
+<REBUS>
+\tMARK;
+\t1:HEART:A
+<ACROSS>
+\tFeline friend
+\tCanine friend
+<DOWN>
+\tTicker
+<NOTEPAD>
+\tFirst line
+\t
+\tSecond line
+"
+        );
+    }
+
+    #[test]
+    fn test_write_round_trip_from_puz() {
+        let data = include_bytes!("../tests/fixtures/puzzleme-example-crossword.puz");
+        let original = crate::puz::parse(data).unwrap();
+
+        let written = write(&original).unwrap();
+        let text = String::from_utf8(written.clone()).unwrap();
+        assert!(!text.contains("<REBUS>"));
+        assert!(!text.contains("<NOTEPAD>"));
+
+        let puzzle = parse(&written).unwrap();
+        assert_eq!(puzzle.title, original.title);
+        assert_eq!(puzzle.copyright, original.copyright);
+        for (clue, original_clue) in puzzle.clues.across.iter().zip(&original.clues.across) {
+            assert_eq!(clue.number, original_clue.number);
+            assert_eq!(clue.text, original_clue.text);
+        }
+        assert_eq!(puzzle.clues.down.len(), original.clues.down.len());
+        assert_eq!(puzzle.grid[1][0].solution, original.grid[1][0].solution);
+    }
+
+    #[test]
+    fn test_write_barred_grid() {
+        let data = include_bytes!("../tests/fixtures/puzzleme-example-crossword.puz");
+        let mut original = crate::puz::parse(data).unwrap();
+        // A bar cuts the first letter off 5-Across, but the format cannot store it
+        original.grid[1][1].bars.left = true;
+
+        let puzzle = parse(&write(&original).unwrap()).unwrap();
+        assert!(puzzle.grid[1][1].bars.is_empty());
+        assert_eq!(puzzle.clues.across.len(), original.clues.across.len());
+        assert_eq!(puzzle.clues.down.len(), original.clues.down.len());
+        assert_eq!(puzzle.clues.across[1].text, original.clues.across[1].text);
+        assert_eq!(puzzle.clues.across[1].length, 5);
+    }
+
+    #[test]
+    fn test_write_requires_solution() {
+        let mut puzzle = parse(SAMPLE.as_bytes()).unwrap();
+        puzzle.grid[0][0].solution = None;
+        assert!(matches!(write(&puzzle), Err(ParseError::InvalidData(_))));
+    }
+}
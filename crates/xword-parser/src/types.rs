@@ -13,6 +13,17 @@ pub struct Puzzle {
     pub clues: Clues,
     pub has_solution: bool,
     pub is_scrambled: bool,
+    /// Solve timer saved with the puzzle, if the file records one.
+    pub timer: Option<Timer>,
+}
+
+/// Saved state of the solve timer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timer {
+    /// Seconds elapsed so far.
+    pub elapsed_seconds: u32,
+    /// Whether the timer was running (rather than paused) when saved.
+    pub is_running: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,27 +35,127 @@ pub struct Cell {
     pub solution: Option<String>,
     /// Multi-character solution for rebus squares.
     pub rebus_solution: Option<String>,
-    /// What the player has entered so far.
+    /// What the player has entered so far. Holds the letter of a given square.
     pub player_value: Option<String>,
+    /// Whether the letter was filled in by the puzzle's author, so the player
+    /// cannot change it.
+    #[serde(default)]
+    pub is_given: bool,
     /// Whether this cell has a circle indicator.
     pub is_circled: bool,
+    /// Thick bars on the cell's edges, which end words like a black square.
+    #[serde(default)]
+    pub bars: Bars,
+    /// Whether this cell is shaded, typically drawn with a gray background.
+    #[serde(default)]
+    pub is_shaded: bool,
+    /// Background color as "#RRGGBB", when the puzzle gives one.
+    #[serde(default)]
+    pub background_color: Option<String>,
     /// Whether the player's answer was previously marked incorrect.
     pub was_incorrect: bool,
+    /// Whether the player's current answer is marked incorrect.
+    pub is_incorrect: bool,
     /// Whether this cell was revealed to the player.
     pub is_revealed: bool,
 }
 
+impl Cell {
+    /// A black square.
+    pub fn black() -> Self {
+        Cell {
+            kind: CellKind::Black,
+            number: None,
+            solution: None,
+            rebus_solution: None,
+            player_value: None,
+            is_given: false,
+            is_circled: false,
+            bars: Bars::default(),
+            is_shaded: false,
+            background_color: None,
+            was_incorrect: false,
+            is_incorrect: false,
+            is_revealed: false,
+        }
+    }
+
+    /// A void square outside the shape of an irregular grid.
+    pub fn void() -> Self {
+        Cell {
+            kind: CellKind::Void,
+            ..Cell::black()
+        }
+    }
+
+    /// An empty letter square with no solution or flags.
+    pub fn letter() -> Self {
+        Cell {
+            kind: CellKind::Letter,
+            ..Cell::black()
+        }
+    }
+}
+
+/// Normalize a hex color such as "f00", "#FF0000" or "ff0000" to "#FF0000".
+pub(crate) fn parse_color(s: &str) -> Option<String> {
+    let hex = s.trim().trim_start_matches('#');
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    match hex.len() {
+        3 => Some(hex.chars().flat_map(|c| [c, c]).collect()),
+        6 => Some(hex.to_string()),
+        _ => None,
+    }
+    .map(|hex| format!("#{}", hex.to_uppercase()))
+}
+
+/// Bars drawn on the edges of a cell in a barred grid.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bars {
+    pub top: bool,
+    pub right: bool,
+    pub bottom: bool,
+    pub left: bool,
+}
+
+impl Bars {
+    /// Whether no edge has a bar.
+    pub fn is_empty(&self) -> bool {
+        !(self.top || self.right || self.bottom || self.left)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum CellKind {
     Black,
     Letter,
+    /// Not part of the grid at all, as in shaped puzzles. Ends words like a
+    /// black square but is drawn as empty space.
+    Void,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Clues {
     pub across: Vec<Clue>,
     pub down: Vec<Clue>,
+    /// Lists in any other direction, such as diagonals or the "Rows" and
+    /// "Bands" of variety puzzles, in file order.
+    #[serde(default)]
+    pub other: Vec<ClueList>,
+}
+
+/// A named list of clues outside the across and down lists.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClueList {
+    /// The direction given by the file (e.g., "Diagonal", "Rows", "Clues").
+    pub direction: String,
+    /// The heading shown above the list.
+    pub label: String,
+    /// Clues whose squares are unknown have a length of 0.
+    pub clues: Vec<Clue>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,4 +170,75 @@ pub struct Clue {
     pub col: usize,
     /// Number of cells in the answer.
     pub length: u8,
+    /// The answer's squares as (row, col) pairs, in answer order. Empty when
+    /// the squares are not known.
+    #[serde(default)]
+    pub cells: Vec<(usize, usize)>,
+    /// Label shown instead of the number, for clues labeled with letters.
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Formatted runs whose text joins to `text`. Empty when the clue has no
+    /// formatting.
+    #[serde(default)]
+    pub rich_text: Vec<TextRun>,
+    /// Word-break hint as given by the file, e.g. "(3,4)" or "5-3".
+    #[serde(default)]
+    pub enumeration: Option<String>,
+    /// Other clues this clue refers to, e.g. "See 5-Down".
+    #[serde(default)]
+    pub references: Vec<ClueRef>,
+    /// Further entries the answer continues into, for clues linked across
+    /// several entries.
+    #[serde(default)]
+    pub continued: Vec<ClueRef>,
+}
+
+/// A run of clue text with one formatting.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextRun {
+    pub text: String,
+    #[serde(default)]
+    pub italic: bool,
+    #[serde(default)]
+    pub bold: bool,
+    #[serde(default)]
+    pub subscript: bool,
+    #[serde(default)]
+    pub superscript: bool,
+    #[serde(default)]
+    pub strikethrough: bool,
+}
+
+impl TextRun {
+    /// Whether any formatting applies to the run.
+    pub fn is_formatted(&self) -> bool {
+        !self.tags().is_empty()
+    }
+
+    /// Whether two runs have the same formatting, ignoring their text.
+    pub fn same_format(&self, other: &TextRun) -> bool {
+        self.tags() == other.tags()
+    }
+
+    /// The HTML tags for the run's formatting, outermost first.
+    pub fn tags(&self) -> Vec<&'static str> {
+        [
+            (self.bold, "b"),
+            (self.italic, "i"),
+            (self.strikethrough, "s"),
+            (self.subscript, "sub"),
+            (self.superscript, "sup"),
+        ]
+        .into_iter()
+        .filter_map(|(on, tag)| on.then_some(tag))
+        .collect()
+    }
+}
+
+/// A reference to another clue by direction and number.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClueRef {
+    /// The direction of the list holding the clue (e.g., "Across").
+    pub direction: String,
+    pub number: u32,
 }
@@ -0,0 +1,280 @@
+//! Parser for the uclick / Universal syndication XML format.
+//!
+//! Metadata elements carry their value in a `v` attribute. The grid is the
+//! row-major `AllAnswer` string, and clue elements inside `<Across>` and
+//! `<Down>` carry the clue number (`cn`), answer (`a`), URL-encoded clue
+//! text (`c`) and 1-based starting square (`n`).
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use crate::error::ParseError;
+use crate::numbering::{direction_step, number_grid, Slot};
+use crate::types::{Cell, Clue, Clues, Puzzle};
+
+/// `AllAnswer` character for a block.
+const BLOCK: char = '-';
+
+/// A clue element before it is matched to the grid.
+struct RawClue {
+    number: u32,
+    text: String,
+}
+
+/// Parse uclick XML into a `Puzzle`.
+pub fn parse(data: &[u8]) -> Result<Puzzle, ParseError> {
+    let mut reader = Reader::from_reader(data);
+    reader.config_mut().trim_text(true);
+
+    let mut title = String::new();
+    let mut author = String::new();
+    let mut copyright = String::new();
+    let mut width: u8 = 0;
+    let mut height: u8 = 0;
+    let mut all_answer = String::new();
+    let mut across_clues: Vec<RawClue> = Vec::new();
+    let mut down_clues: Vec<RawClue> = Vec::new();
+    let mut current_direction: Option<bool> = None; // true = across
+
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Eof) => break,
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                let local_name = e.local_name();
+                let name = std::str::from_utf8(local_name.as_ref())
+                    .unwrap_or("")
+                    .to_lowercase();
+                match name.as_str() {
+                    "title" => title = attribute(&e, "v")?,
+                    "author" => author = attribute(&e, "v")?,
+                    "copyright" => copyright = attribute(&e, "v")?,
+                    "width" => width = attribute(&e, "v")?.parse().unwrap_or(0),
+                    "height" => height = attribute(&e, "v")?.parse().unwrap_or(0),
+                    "allanswer" => all_answer = attribute(&e, "v")?,
+                    "across" => current_direction = Some(true),
+                    "down" => current_direction = Some(false),
+                    _ => {
+                        if let Some(across) = current_direction {
+                            let number = attribute(&e, "cn")?.parse().map_err(|_| {
+                                ParseError::InvalidData(format!(
+                                    "clue element <{}> has no valid cn",
+                                    name
+                                ))
+                            })?;
+                            let clue = RawClue {
+                                number,
+                                text: percent_decode(&attribute(&e, "c")?),
+                            };
+                            if across {
+                                across_clues.push(clue);
+                            } else {
+                                down_clues.push(clue);
+                            }
+                        }
+                    }
+                }
+            }
+            Ok(Event::End(e)) => {
+                let local_name = e.local_name();
+                let name = std::str::from_utf8(local_name.as_ref()).unwrap_or("");
+                if name.eq_ignore_ascii_case("across") || name.eq_ignore_ascii_case("down") {
+                    current_direction = None;
+                }
+            }
+            Ok(_) => {}
+            Err(e) => return Err(ParseError::Xml(e.to_string())),
+        }
+        buf.clear();
+    }
+
+    if width == 0 || height == 0 {
+        return Err(ParseError::InvalidDimensions { width, height });
+    }
+    let letters: Vec<char> = all_answer.chars().collect();
+    if letters.len() != width as usize * height as usize {
+        return Err(ParseError::InvalidData(format!(
+            "AllAnswer has {} squares, expected {}",
+            letters.len(),
+            width as usize * height as usize
+        )));
+    }
+
+    let mut grid: Vec<Vec<Cell>> = letters
+        .chunks(width as usize)
+        .map(|row| {
+            row.iter()
+                .map(|&c| {
+                    if c == BLOCK {
+                        Cell::black()
+                    } else {
+                        Cell {
+                            solution: Some(c.to_uppercase().to_string()),
+                            ..Cell::letter()
+                        }
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    let (across_slots, down_slots) = number_grid(&mut grid);
+    let across = match_clues(across_clues, &across_slots, "across")?;
+    let down = match_clues(down_clues, &down_slots, "down")?;
+
+    Ok(Puzzle {
+        title,
+        author,
+        copyright,
+        notes: String::new(),
+        width,
+        height,
+        grid,
+        clues: Clues {
+            across,
+            down,
+            other: Vec::new(),
+        },
+        has_solution: true,
+        is_scrambled: false,
+        timer: None,
+    })
+}
+
+/// Pair clue elements with numbered grid slots by clue number.
+fn match_clues(
+    clues: Vec<RawClue>,
+    slots: &[Slot],
+    direction: &str,
+) -> Result<Vec<Clue>, ParseError> {
+    clues
+        .into_iter()
+        .map(|clue| {
+            let slot = slots
+                .iter()
+                .find(|s| s.number == clue.number)
+                .ok_or_else(|| {
+                    ParseError::InvalidData(format!(
+                        "no {} word numbered {}",
+                        direction, clue.number
+                    ))
+                })?;
+            Ok(Clue {
+                number: clue.number,
+                text: clue.text,
+                row: slot.row,
+                col: slot.col,
+                length: slot.length,
+                cells: slot.cells(direction_step(direction)),
+                label: None,
+                rich_text: Vec::new(),
+                enumeration: None,
+                references: Vec::new(),
+                continued: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+/// The unescaped value of an attribute, or an empty string if it is missing.
+fn attribute(e: &BytesStart, key: &str) -> Result<String, ParseError> {
+    for attr in e.attributes().flatten() {
+        if attr.key.as_ref() == key.as_bytes() {
+            return attr
+                .unescape_value()
+                .map(|v| v.into_owned())
+                .map_err(|e| ParseError::Xml(e.to_string()));
+        }
+    }
+    Ok(String::new())
+}
+
+/// Decode %XX escapes, as used in clue text.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::CellKind;
+
+    const SAMPLE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<crossword>
+  <Title v="Daily Puzzle" />
+  <Author v="Ann Setter" />
+  <Editor v="Bo Editor" />
+  <Copyright v="2024 Andrews &amp; McMeel" />
+  <Width v="3" />
+  <Height v="3" />
+  <AllAnswer v="CAT-O-DOG" />
+  <across>
+    <a1 a="CAT" c="Feline%20friend" n="1" cn="1" />
+    <a2 a="DOG" c="Canine%2C%20say" n="7" cn="3" />
+  </across>
+  <down>
+    <d1 a="AOO" c="Ticker" n="2" cn="2" />
+  </down>
+</crossword>"#;
+
+    #[test]
+    fn test_parse_sample() {
+        let puzzle = parse(SAMPLE.as_bytes()).unwrap();
+        assert_eq!(puzzle.title, "Daily Puzzle");
+        assert_eq!(puzzle.author, "Ann Setter");
+        assert_eq!(puzzle.copyright, "2024 Andrews & McMeel");
+        assert_eq!((puzzle.width, puzzle.height), (3, 3));
+
+        assert_eq!(puzzle.grid[0][1].number, Some(2));
+        assert!(matches!(puzzle.grid[1][0].kind, CellKind::Black));
+        assert_eq!(puzzle.grid[2][2].solution.as_deref(), Some("G"));
+
+        assert_eq!(puzzle.clues.across.len(), 2);
+        assert_eq!(puzzle.clues.across[1].text, "Canine, say");
+        assert_eq!(
+            (puzzle.clues.across[1].row, puzzle.clues.across[1].col),
+            (2, 0)
+        );
+        assert_eq!(puzzle.clues.down[0].length, 3);
+    }
+
+    #[test]
+    fn test_parse_rejects_mismatched_grid() {
+        let short = SAMPLE.replace("CAT-O-DOG", "CAT-O-DO");
+        assert!(matches!(
+            parse(short.as_bytes()),
+            Err(ParseError::InvalidData(_))
+        ));
+        let unknown = SAMPLE.replace(r#"cn="3""#, r#"cn="4""#);
+        assert!(matches!(
+            parse(unknown.as_bytes()),
+            Err(ParseError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("A%20b%2Cc"), "A b,c");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("caf%C3%A9"), "café");
+    }
+}
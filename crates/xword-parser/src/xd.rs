@@ -0,0 +1,548 @@
+//! Parser and writer for the xd plain-text crossword format (.xd).
+//!
+//! File format reference:
+//! https://github.com/century-arcade/xd/blob/master/doc/xd-format.md
+
+use std::collections::HashMap;
+
+use crate::error::ParseError;
+use crate::numbering::{direction_step, number_grid, number_grid_without_bars, Slot};
+use crate::puz::decode_string;
+use crate::text::{single_line, REBUS_SYMBOLS};
+use crate::types::{Cell, CellKind, Clue, Clues, Puzzle};
+
+/// Grid character for a block.
+const BLOCK: char = '#';
+
+/// Grid character for a square outside a shaped grid.
+const NON_CELL: char = '_';
+
+/// Separator between a clue and its answer.
+const ANSWER_SEPARATOR: &str = " ~ ";
+
+/// Parse an xd file into a `Puzzle`.
+pub fn parse(data: &[u8]) -> Result<Puzzle, ParseError> {
+    let text = decode_string(data);
+    let sections = split_sections(&text);
+    let (metadata, sections) = split_metadata(&sections);
+    let [grid_lines, clue_lines, notes @ ..] = sections else {
+        return Err(ParseError::InvalidData(
+            "expected grid and clue sections".into(),
+        ));
+    };
+
+    let mut fields: HashMap<String, String> = HashMap::new();
+    for line in metadata {
+        if let Some((key, value)) = line.split_once(':') {
+            fields.insert(key.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+    let field = |key: &str| fields.get(key).cloned().unwrap_or_default();
+
+    let rebus_table = parse_rebus(&field("rebus"))?;
+    let mut grid = parse_grid(grid_lines, &rebus_table)?;
+    let height = dimension(grid.len())?;
+    let width = dimension(grid[0].len())?;
+
+    let (across_slots, down_slots) = number_grid(&mut grid);
+    let mut across = Vec::new();
+    let mut down = Vec::new();
+    for line in clue_lines {
+        let (direction, number, text) = parse_clue_line(line)?;
+        let (slots, clues, name) = match direction {
+            'A' => (&across_slots, &mut across, "across"),
+            _ => (&down_slots, &mut down, "down"),
+        };
+        let slot = find_slot(slots, number).ok_or_else(|| {
+            ParseError::InvalidData(format!("no {} word numbered {}", name, number))
+        })?;
+        clues.push(Clue {
+            number,
+            text,
+            row: slot.row,
+            col: slot.col,
+            length: slot.length,
+            cells: slot.cells(direction_step(name)),
+            label: None,
+            rich_text: Vec::new(),
+            enumeration: None,
+            references: Vec::new(),
+            continued: Vec::new(),
+        });
+    }
+
+    let mut notes = notes.concat().join("\n").trim().to_string();
+    if notes.is_empty() {
+        notes = field("notes");
+    }
+
+    Ok(Puzzle {
+        title: field("title"),
+        author: field("author"),
+        copyright: field("copyright"),
+        notes,
+        width,
+        height,
+        grid,
+        clues: Clues {
+            across,
+            down,
+            other: Vec::new(),
+        },
+        has_solution: true,
+        is_scrambled: false,
+        timer: None,
+    })
+}
+
+/// Split the file into sections separated by two or more blank lines.
+/// Single blank lines inside a section are dropped.
+fn split_sections(text: &str) -> Vec<Vec<&str>> {
+    let mut sections: Vec<Vec<&str>> = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut blank_run = 0;
+
+    for line in text.lines().map(str::trim_end) {
+        if line.trim().is_empty() {
+            blank_run += 1;
+            continue;
+        }
+        if blank_run >= 2 && !current.is_empty() {
+            sections.push(std::mem::take(&mut current));
+        }
+        blank_run = 0;
+        current.push(line);
+    }
+    if !current.is_empty() {
+        sections.push(current);
+    }
+    sections
+}
+
+/// Whether text has the layout of an xd file: an optional `Key: Value`
+/// header, then a grid section and a clue section separated by blank lines.
+pub(crate) fn looks_like_xd(text: &str) -> bool {
+    let sections = split_sections(text);
+    let (_, sections) = split_metadata(&sections);
+    let [grid_lines, clue_lines, ..] = sections else {
+        return false;
+    };
+    let width = grid_lines[0].trim().chars().count();
+    let is_grid = grid_lines.iter().all(|line| {
+        let line = line.trim();
+        line.chars().count() == width && !line.contains(|c: char| c.is_whitespace() || c == ':')
+    });
+    is_grid && parse_clue_line(clue_lines[0]).is_ok()
+}
+
+/// Split off the metadata section, which is optional.
+fn split_metadata<'a, 'b>(sections: &'a [Vec<&'b str>]) -> (&'a [&'b str], &'a [Vec<&'b str>]) {
+    match sections.split_first() {
+        Some((first, rest)) if first.iter().all(|line| is_metadata_line(line)) => (first, rest),
+        _ => (&[], sections),
+    }
+}
+
+/// Whether a line is a `Key: Value` header line.
+fn is_metadata_line(line: &str) -> bool {
+    line.split_once(':').is_some_and(|(key, _)| {
+        !key.trim().is_empty()
+            && key
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_')
+    })
+}
+
+/// Parse a `Rebus:` value such as "1=HEART 2=DIAMOND".
+fn parse_rebus(value: &str) -> Result<HashMap<char, String>, ParseError> {
+    let mut table = HashMap::new();
+    for entry in value.split_whitespace() {
+        let invalid = || ParseError::InvalidData(format!("invalid rebus entry: {}", entry));
+        let (key, answer) = entry.split_once('=').ok_or_else(invalid)?;
+        let mut key_chars = key.chars();
+        let (Some(key), None) = (key_chars.next(), key_chars.next()) else {
+            return Err(invalid());
+        };
+        if answer.is_empty() {
+            return Err(invalid());
+        }
+        table.insert(key, answer.to_uppercase());
+    }
+    Ok(table)
+}
+
+/// Parse the grid block. Lowercase letters are circled squares.
+fn parse_grid(
+    lines: &[&str],
+    rebus_table: &HashMap<char, String>,
+) -> Result<Vec<Vec<Cell>>, ParseError> {
+    let width = lines[0].trim().chars().count();
+    let mut grid = Vec::with_capacity(lines.len());
+
+    for (row, line) in lines.iter().enumerate() {
+        let chars: Vec<char> = line.trim().chars().collect();
+        if chars.len() != width {
+            return Err(ParseError::InvalidData(format!(
+                "row {} has {} cells, expected {}",
+                row,
+                chars.len(),
+                width
+            )));
+        }
+        grid.push(
+            chars
+                .into_iter()
+                .map(|c| parse_grid_char(c, rebus_table))
+                .collect::<Result<_, _>>()?,
+        );
+    }
+    Ok(grid)
+}
+
+/// Map one grid character to a cell.
+fn parse_grid_char(c: char, rebus_table: &HashMap<char, String>) -> Result<Cell, ParseError> {
+    if c == BLOCK {
+        return Ok(Cell::black());
+    }
+    if c == NON_CELL {
+        return Ok(Cell::void());
+    }
+    if let Some(answer) = rebus_table.get(&c) {
+        return Ok(Cell {
+            solution: answer.chars().next().map(String::from),
+            rebus_solution: Some(answer.clone()),
+            ..Cell::letter()
+        });
+    }
+    if c.is_alphabetic() {
+        return Ok(Cell {
+            solution: Some(c.to_uppercase().to_string()),
+            is_circled: c.is_lowercase(),
+            ..Cell::letter()
+        });
+    }
+    Err(ParseError::InvalidData(format!(
+        "invalid grid character: {:?}",
+        c
+    )))
+}
+
+/// Parse a clue line such as "A1. Feline friend ~ CAT".
+/// The answer is dropped since the grid already holds it.
+fn parse_clue_line(line: &str) -> Result<(char, u32, String), ParseError> {
+    let invalid = || ParseError::InvalidData(format!("invalid clue line: {}", line));
+    let mut chars = line.chars();
+    let direction = chars
+        .next()
+        .map(|c| c.to_ascii_uppercase())
+        .filter(|c| *c == 'A' || *c == 'D')
+        .ok_or_else(invalid)?;
+    let (number, rest) = chars.as_str().split_once('.').ok_or_else(invalid)?;
+    let number: u32 = number.trim().parse().map_err(|_| invalid())?;
+    let text = match rest.rsplit_once(ANSWER_SEPARATOR.trim()) {
+        Some((text, _answer)) => text,
+        None => rest,
+    };
+    Ok((direction, number, text.trim().to_string()))
+}
+
+fn find_slot(slots: &[Slot], number: u32) -> Option<&Slot> {
+    slots.iter().find(|slot| slot.number == number)
+}
+
+fn dimension(n: usize) -> Result<u8, ParseError> {
+    u8::try_from(n)
+        .ok()
+        .filter(|&n| n > 0)
+        .ok_or_else(|| ParseError::InvalidData(format!("unsupported grid size: {}", n)))
+}
+
+/// Serialize a `Puzzle` to xd text.
+///
+/// Circled squares are written in lowercase and rebus squares get a key from
+/// the `Rebus:` header. Each clue is followed by its answer, read from the grid.
+pub fn write(puzzle: &Puzzle) -> Result<Vec<u8>, ParseError> {
+    let mut rebus_entries: Vec<(char, String)> = Vec::new();
+    let mut keys = REBUS_SYMBOLS.chars();
+    let mut grid_lines = Vec::with_capacity(puzzle.grid.len());
+
+    for row in &puzzle.grid {
+        let mut line = String::with_capacity(row.len());
+        for cell in row {
+            match cell.kind {
+                CellKind::Black => {
+                    line.push(BLOCK);
+                    continue;
+                }
+                CellKind::Void => {
+                    line.push(NON_CELL);
+                    continue;
+                }
+                CellKind::Letter => {}
+            }
+            let answer = cell_answer(cell)?;
+            let mut chars = answer.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_alphabetic() => {
+                    if cell.is_circled {
+                        line.extend(c.to_lowercase());
+                    } else {
+                        line.push(c);
+                    }
+                }
+                _ => {
+                    let key = match rebus_entries.iter().find(|(_, a)| *a == answer) {
+                        Some((key, _)) => *key,
+                        None => {
+                            let key = keys.next().ok_or_else(|| {
+                                ParseError::InvalidData("too many distinct rebus answers".into())
+                            })?;
+                            rebus_entries.push((key, answer));
+                            key
+                        }
+                    };
+                    line.push(key);
+                }
+            }
+        }
+        grid_lines.push(line);
+    }
+
+    // The title is always written so the metadata section is never empty,
+    // which would leave a file starting with blank lines
+    let mut out = format!("Title: {}", single_line(&puzzle.title))
+        .trim_end()
+        .to_string();
+    out.push('\n');
+    for (key, value) in [("Author", &puzzle.author), ("Copyright", &puzzle.copyright)] {
+        if !value.is_empty() {
+            out.push_str(&format!("{}: {}\n", key, single_line(value)));
+        }
+    }
+    if !rebus_entries.is_empty() {
+        let entries: Vec<String> = rebus_entries
+            .iter()
+            .map(|(key, answer)| format!("{}={}", key, answer))
+            .collect();
+        out.push_str(&format!("Rebus: {}\n", entries.join(" ")));
+    }
+
+    out.push_str("\n\n");
+    for line in &grid_lines {
+        out.push_str(line);
+        out.push('\n');
+    }
+
+    // Bars cannot be written, so clues follow the grid as it will be read back
+    let (across_slots, down_slots) = number_grid_without_bars(&puzzle.grid);
+    out.push_str("\n\n");
+    write_clues(
+        &mut out,
+        'A',
+        &across_slots,
+        &puzzle.clues.across,
+        puzzle,
+        0,
+        1,
+    )?;
+    out.push('\n');
+    write_clues(&mut out, 'D', &down_slots, &puzzle.clues.down, puzzle, 1, 0)?;
+
+    if !puzzle.notes.is_empty() {
+        out.push_str("\n\n");
+        out.push_str(puzzle.notes.trim());
+        out.push('\n');
+    }
+
+    Ok(out.into_bytes())
+}
+
+/// Write one direction's clue lines, matching clues to slots by starting cell.
+fn write_clues(
+    out: &mut String,
+    direction: char,
+    slots: &[Slot],
+    clues: &[Clue],
+    puzzle: &Puzzle,
+    dr: usize,
+    dc: usize,
+) -> Result<(), ParseError> {
+    for slot in slots {
+        let text = clues
+            .iter()
+            .find(|c| c.row == slot.row && c.col == slot.col)
+            .map(|c| single_line(&c.text))
+            .unwrap_or_default();
+        let mut answer = String::new();
+        for i in 0..slot.length as usize {
+            answer.push_str(&cell_answer(
+                &puzzle.grid[slot.row + i * dr][slot.col + i * dc],
+            )?);
+        }
+        out.push_str(&format!(
+            "{}{}. {}{}{}\n",
+            direction, slot.number, text, ANSWER_SEPARATOR, answer
+        ));
+    }
+    Ok(())
+}
+
+/// The full uppercase answer for a letter square.
+fn cell_answer(cell: &Cell) -> Result<String, ParseError> {
+    cell.rebus_solution
+        .as_deref()
+        .or(cell.solution.as_deref())
+        .map(str::to_uppercase)
+        .ok_or_else(|| ParseError::InvalidData("every square needs a solution in xd".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "Title: Test Puzzle
+Author: Test Author
+Editor: Test Editor
+Copyright: 2024 Test
+Rebus: 1=HEART
+
+
+C1T
+#o#
+DOG
+
+
+A1. Feline friend ~ CHEARTT
+A3. Canine friend ~ DOG
+
+D2. Ticker ~ HEARTOO
+
+
+First line
+Second line
+";
+
+    #[test]
+    fn test_parse_sample() {
+        let puzzle = parse(SAMPLE.as_bytes()).unwrap();
+        assert_eq!(puzzle.title, "Test Puzzle");
+        assert_eq!(puzzle.author, "Test Author");
+        assert_eq!(puzzle.copyright, "2024 Test");
+        assert_eq!(puzzle.notes, "First line\nSecond line");
+        assert_eq!((puzzle.width, puzzle.height), (3, 3));
+
+        let rebus = &puzzle.grid[0][1];
+        assert_eq!(rebus.solution.as_deref(), Some("H"));
+        assert_eq!(rebus.rebus_solution.as_deref(), Some("HEART"));
+        assert!(matches!(puzzle.grid[1][0].kind, CellKind::Black));
+        assert!(puzzle.grid[1][1].is_circled);
+        assert_eq!(puzzle.grid[1][1].solution.as_deref(), Some("O"));
+
+        assert_eq!(puzzle.clues.across.len(), 2);
+        assert_eq!(puzzle.clues.across[1].text, "Canine friend");
+        assert_eq!(
+            (puzzle.clues.across[1].row, puzzle.clues.across[1].col),
+            (2, 0)
+        );
+        assert_eq!(puzzle.clues.down[0].number, 2);
+        assert_eq!(puzzle.clues.down[0].text, "Ticker");
+        assert_eq!(puzzle.clues.down[0].length, 3);
+    }
+
+    #[test]
+    fn test_parse_clue_without_answer() {
+        let (direction, number, text) = parse_clue_line("D12. What's up, doc?").unwrap();
+        assert_eq!((direction, number), ('D', 12));
+        assert_eq!(text, "What's up, doc?");
+        assert!(parse_clue_line("X1. Nope ~ NO").is_err());
+        assert!(parse_clue_line("A. Nope").is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_clue_number() {
+        let data = SAMPLE.replace("A3.", "A7.");
+        assert!(matches!(
+            parse(data.as_bytes()),
+            Err(ParseError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_rejects_missing_sections() {
+        assert!(parse(b"Title: Only metadata\n").is_err());
+        assert!(parse(b"Title: x\n\n\nAB\nC\n\n\nA1. x ~ AB\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_without_metadata() {
+        let puzzle = parse(b"\n\nAB\nCD\n\n\nA1. Top ~ AB\nA3. Bottom ~ CD\n").unwrap();
+        assert_eq!(puzzle.title, "");
+        assert_eq!(puzzle.clues.across[1].text, "Bottom");
+    }
+
+    #[test]
+    fn test_untitled_round_trip() {
+        let mut original = parse(SAMPLE.as_bytes()).unwrap();
+        original.title.clear();
+        original.author.clear();
+        original.copyright.clear();
+        for row in &mut original.grid {
+            for cell in row {
+                cell.rebus_solution = None;
+            }
+        }
+
+        let written = String::from_utf8(write(&original).unwrap()).unwrap();
+        assert!(written.starts_with("Title:\n\n\nC"));
+        let puzzle = parse(written.as_bytes()).unwrap();
+        assert_eq!(puzzle.title, "");
+        assert_eq!(puzzle.clues.down[0].text, "Ticker");
+    }
+
+    #[test]
+    fn test_write_round_trip() {
+        let puzzle = parse(SAMPLE.as_bytes()).unwrap();
+        let written = String::from_utf8(write(&puzzle).unwrap()).unwrap();
+        assert_eq!(written, SAMPLE.replace("Editor: Test Editor\n", ""));
+    }
+
+    #[test]
+    fn test_void_squares_round_trip() {
+        let data = SAMPLE.replace("#o#", "_o#");
+        let puzzle = parse(data.as_bytes()).unwrap();
+        assert!(matches!(puzzle.grid[1][0].kind, CellKind::Void));
+        assert!(matches!(puzzle.grid[1][2].kind, CellKind::Black));
+        let written = String::from_utf8(write(&puzzle).unwrap()).unwrap();
+        assert!(written.contains("\n_o#\n"));
+    }
+
+    #[test]
+    fn test_write_barred_grid() {
+        let data = include_bytes!("../tests/fixtures/puzzleme-example-crossword.puz");
+        let mut original = crate::puz::parse(data).unwrap();
+        // A bar cuts the first letter off 5-Across, but the format cannot store it
+        original.grid[1][1].bars.left = true;
+
+        let puzzle = parse(&write(&original).unwrap()).unwrap();
+        assert!(puzzle.grid[1][1].bars.is_empty());
+        assert_eq!(puzzle.clues.across.len(), original.clues.across.len());
+        assert_eq!(puzzle.clues.down.len(), original.clues.down.len());
+        assert_eq!(puzzle.clues.across[1].text, original.clues.across[1].text);
+        assert_eq!(puzzle.clues.across[1].length, 5);
+    }
+
+    #[test]
+    fn test_write_from_puz() {
+        let data = include_bytes!("../tests/fixtures/puzzleme-example-crossword.puz");
+        let original = crate::puz::parse(data).unwrap();
+        let puzzle = parse(&write(&original).unwrap()).unwrap();
+
+        assert_eq!(puzzle.title, original.title);
+        assert_eq!(puzzle.clues.across.len(), original.clues.across.len());
+        assert_eq!(puzzle.clues.down.len(), original.clues.down.len());
+        for (clue, original_clue) in puzzle.clues.down.iter().zip(&original.clues.down) {
+            assert_eq!(clue.number, original_clue.number);
+            assert_eq!(clue.text, original_clue.text);
+        }
+    }
+}
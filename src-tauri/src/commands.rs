@@ -1,5 +1,6 @@
-use log::{error, info};
-use xword_parser::Puzzle;
+use log::{error, info, warn};
+use xword_parser::puz::{ParseOptions, Unlock};
+use xword_parser::{Format, ParseError, Puzzle};
 
 #[tauri::command]
 pub fn open_puzzle(file_path: String) -> Result<Puzzle, String> {
@@ -10,9 +11,26 @@ pub fn open_puzzle(file_path: String) -> Result<Puzzle, String> {
         format!("Failed to read file: {e}")
     })?;
 
+    // Trust an extension that names one format; otherwise look at the contents
     let extension = file_path.rsplit('.').next().unwrap_or("");
+    let format = Format::from_file(extension, &data).ok_or_else(|| {
+        error!("Unrecognized puzzle format: {file_path}");
+        ParseError::UnsupportedFormat(extension.to_lowercase()).to_string()
+    })?;
+
+    // .puz files are loaded leniently, but corrupt or truncated downloads are logged
+    let result = if format == Format::Puz {
+        xword_parser::puz::parse_with_options(&data, &ParseOptions::default()).map(|parsed| {
+            for failure in &parsed.checksum_failures {
+                warn!("{file_path}: {failure}");
+            }
+            parsed.puzzle
+        })
+    } else {
+        xword_parser::parse_format(&data, format)
+    };
 
-    let puzzle = xword_parser::parse(&data, extension).map_err(|e| {
+    let puzzle = result.map_err(|e| {
         error!("Failed to parse puzzle {file_path}: {e}");
         e.to_string()
     })?;
@@ -24,3 +42,31 @@ pub fn open_puzzle(file_path: String) -> Result<Puzzle, String> {
 
     Ok(puzzle)
 }
+
+/// Unlock a scrambled .puz file with its four-digit key, or by trying every
+/// key when none is given, so the solution can be checked and revealed.
+#[tauri::command]
+pub fn unlock_puzzle(file_path: String, key: Option<u16>) -> Result<Puzzle, String> {
+    info!("Unlocking puzzle: {file_path}");
+
+    let data = std::fs::read(&file_path).map_err(|e| {
+        error!("Failed to read file {file_path}: {e}");
+        format!("Failed to read file: {e}")
+    })?;
+
+    let options = ParseOptions {
+        unlock: Some(key.map_or(Unlock::BruteForce, Unlock::Key)),
+        ..ParseOptions::default()
+    };
+
+    let parsed = xword_parser::puz::parse_with_options(&data, &options).map_err(|e| {
+        error!("Failed to unlock puzzle {file_path}: {e}");
+        e.to_string()
+    })?;
+
+    if let Some(key) = parsed.unlock_key {
+        info!("Unlocked puzzle {file_path} with key {key:04}");
+    }
+
+    Ok(parsed.puzzle)
+}
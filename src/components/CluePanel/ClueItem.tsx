@@ -1,5 +1,5 @@
-import { useRef, useEffect } from "react";
-import type { Clue } from "../../types/puzzle";
+import { Fragment, useRef, useEffect, type ReactNode } from "react";
+import type { Clue, TextRun } from "../../types/puzzle";
 import type { ClueHighlight } from "./ClueList";
 
 const SCROLL_DURATION_MS = 200;
@@ -31,6 +31,27 @@ function smoothScrollTo(container: Element, targetTop: number) {
   requestAnimationFrame(step);
 }
 
+/** The enumeration to show after the clue text, unless the text has it. */
+function getEnumerationSuffix(clue: Clue): string {
+  if (!clue.enumeration) return "";
+  const enumeration = clue.enumeration.startsWith("(")
+    ? clue.enumeration
+    : `(${clue.enumeration})`;
+  if (clue.text.endsWith(enumeration)) return "";
+  return ` ${enumeration}`;
+}
+
+/** A run of clue text wrapped in the elements for its formatting. */
+function renderRun(run: TextRun, index: number) {
+  let node: ReactNode = run.text;
+  if (run.subscript) node = <sub>{node}</sub>;
+  if (run.superscript) node = <sup>{node}</sup>;
+  if (run.strikethrough) node = <s>{node}</s>;
+  if (run.italic) node = <i>{node}</i>;
+  if (run.bold) node = <b>{node}</b>;
+  return <Fragment key={index}>{node}</Fragment>;
+}
+
 function getClassName(highlight: ClueHighlight, isComplete: boolean): string {
   const base = "cursor-pointer rounded px-2 py-1 text-sm";
 
@@ -90,10 +111,11 @@ export default function ClueItem({
     <li
       ref={ref}
       onClick={() => onClick(clue)}
-      data-number={`${clue.number}.`}
+      data-number={`${clue.label ?? clue.number}.`}
       className={`clue-item ${getClassName(highlight, isComplete)}`}
     >
-      {clue.text}
+      {clue.rich_text.length > 0 ? clue.rich_text.map(renderRun) : clue.text}
+      {getEnumerationSuffix(clue)}
     </li>
   );
 }
@@ -10,8 +10,9 @@ interface ClueListProps {
   crossClueNumber: number | null;
   completedClueNumbers: Set<number>;
   scrollToTop: boolean;
-  onClueClick: (clue: Clue, direction: Direction) => void;
-  direction: Direction;
+  onClueClick: (clue: Clue, direction?: Direction) => void;
+  /** Unset for lists outside across and down. */
+  direction?: Direction;
 }
 
 function getHighlight(
@@ -42,7 +43,7 @@ export default function ClueList({
       <ol className="min-h-0 flex-1 overflow-y-auto px-1 py-1">
         {clues.map((clue) => (
           <ClueItem
-            key={clue.number}
+            key={clue.label ?? clue.number}
             clue={clue}
             highlight={getHighlight(
               clue.number,
@@ -9,6 +9,9 @@ import type { Clue, Direction } from "../../types/puzzle";
 import { useSettingsStore } from "../../store/settingsStore";
 import ClueList from "./ClueList";
 
+/** Completed set for lists that are not tracked by direction. */
+const NO_CLUES = new Set<number>();
+
 /** Redacted clue list shown when paused — shows numbers with gray bars. */
 function RedactedClueList({ title, clues }: { title: string; clues: Clue[] }) {
   return (
@@ -18,9 +21,12 @@ function RedactedClueList({ title, clues }: { title: string; clues: Clue[] }) {
       </h3>
       <ol className="min-h-0 flex-1 overflow-y-auto px-1 py-1">
         {clues.map((clue) => (
-          <li key={clue.number} className="flex items-center gap-2 px-2 py-1">
+          <li
+            key={clue.label ?? clue.number}
+            className="flex items-center gap-2 px-2 py-1"
+          >
             <span className="text-sm text-gray-300 dark:text-gray-600">
-              {clue.number}.
+              {clue.label ?? clue.number}.
             </span>
             <span className="h-3 flex-1 rounded bg-gray-200 dark:bg-gray-700" />
           </li>
@@ -43,10 +49,12 @@ export default function CluePanel() {
     (s) => s.settings.navigation.scroll_clue_to_top,
   );
 
-  const handleClueClick = useCallback((clue: Clue, dir: Direction) => {
+  const handleClueClick = useCallback((clue: Clue, dir?: Direction) => {
+    // Clues whose squares are unknown have nowhere to go
+    if (clue.length === 0) return;
     const state = usePuzzleStore.getState();
     state.setCursor(clue.row, clue.col);
-    state.setDirection(dir);
+    if (dir) state.setDirection(dir);
   }, []);
 
   // Compute which clues are fully filled in
@@ -101,6 +109,13 @@ export default function CluePanel() {
           <>
             <RedactedClueList title="Across" clues={puzzle.clues.across} />
             <RedactedClueList title="Down" clues={puzzle.clues.down} />
+            {puzzle.clues.other.map((list) => (
+              <RedactedClueList
+                key={list.label}
+                title={list.label}
+                clues={list.clues}
+              />
+            ))}
           </>
         ) : (
           <>
@@ -124,6 +139,18 @@ export default function CluePanel() {
               onClueClick={handleClueClick}
               direction="down"
             />
+            {puzzle.clues.other.map((list) => (
+              <ClueList
+                key={list.label}
+                title={list.label}
+                clues={list.clues}
+                primaryClueNumber={null}
+                crossClueNumber={null}
+                completedClueNumbers={NO_CLUES}
+                scrollToTop={scrollToTop}
+                onClueClick={handleClueClick}
+              />
+            ))}
           </>
         )}
       </div>
@@ -1,22 +1,51 @@
 import { describe, it, expect } from "vitest";
 import { hitTest, computeCellSize, getCanvasDimensions } from "./GridRenderer";
 import { BORDER_WIDTH, MIN_CELL_SIZE, MAX_CELL_SIZE } from "./constants";
-import type { Puzzle, Cell } from "../../types/puzzle";
+import type { Puzzle, Cell, CellKind, Clue } from "../../types/puzzle";
 
-function makeCell(kind: "black" | "letter", overrides?: Partial<Cell>): Cell {
+function makeCell(kind: CellKind, overrides?: Partial<Cell>): Cell {
   return {
     kind,
     number: null,
     solution: null,
     rebus_solution: null,
     player_value: null,
+    is_given: false,
     is_circled: false,
+    bars: { top: false, right: false, bottom: false, left: false },
+    is_shaded: false,
+    background_color: null,
     was_incorrect: false,
+    is_incorrect: false,
     is_revealed: false,
     ...overrides,
   };
 }
 
+function makeClue(
+  number: number,
+  text: string,
+  row: number,
+  col: number,
+  length: number,
+  overrides?: Partial<Clue>,
+): Clue {
+  return {
+    number,
+    text,
+    row,
+    col,
+    length,
+    cells: [],
+    label: null,
+    rich_text: [],
+    enumeration: null,
+    references: [],
+    continued: [],
+    ...overrides,
+  };
+}
+
 function makeSmallPuzzle(): Puzzle {
   return {
     title: "Test",
@@ -35,11 +64,13 @@ function makeSmallPuzzle(): Puzzle {
       [makeCell("letter"), makeCell("letter"), makeCell("letter")],
     ],
     clues: {
-      across: [{ number: 1, text: "Clue", row: 0, col: 0, length: 3 }],
-      down: [{ number: 1, text: "Clue", row: 0, col: 0, length: 3 }],
+      across: [makeClue(1, "Clue", 0, 0, 3)],
+      down: [makeClue(1, "Clue", 0, 0, 3)],
+      other: [],
     },
     has_solution: true,
     is_scrambled: false,
+    timer: null,
   };
 }
 
@@ -65,6 +96,14 @@ describe("hitTest", () => {
     expect(hitTest(x, y, puzzle, cellSize)).toBeNull();
   });
 
+  it("returns null for void cells", () => {
+    const shaped = makeSmallPuzzle();
+    shaped.grid[0][2] = makeCell("void");
+    const x = BORDER_WIDTH + 2 * cellSize + 20;
+    const y = BORDER_WIDTH + 20;
+    expect(hitTest(x, y, shaped, cellSize)).toBeNull();
+  });
+
   it("returns null for clicks outside the grid (negative)", () => {
     expect(hitTest(-5, -5, puzzle, cellSize)).toBeNull();
   });
@@ -1,6 +1,7 @@
 import type { Puzzle, CursorPosition, Direction } from "../../types/puzzle";
 import type { ColorPalette } from "./constants";
 import {
+  BAR_WIDTH,
   BORDER_WIDTH,
   CELL_BORDER_WIDTH,
   NUMBER_FONT_RATIO,
@@ -72,6 +73,9 @@ export function renderGrid(
       const x = borderWidth + col * cs;
       const y = borderWidth + row * cs;
 
+      // Void squares lie outside the grid's shape and are left unpainted
+      if (cell.kind === "void") continue;
+
       // Cell background
       if (cell.kind === "black") {
         ctx.fillStyle = colors.blackCell;
@@ -79,6 +83,10 @@ export function renderGrid(
         ctx.fillStyle = colors.cursorCell;
       } else if (wordCellSet.has(`${row},${col}`)) {
         ctx.fillStyle = colors.wordHighlight;
+      } else if (cell.background_color) {
+        ctx.fillStyle = cell.background_color;
+      } else if (cell.is_shaded) {
+        ctx.fillStyle = colors.shadedCell;
       } else {
         ctx.fillStyle = colors.cellBackground;
       }
@@ -140,16 +148,19 @@ export function renderGrid(
         } else {
           ctx.fillStyle = colors.letterText;
         }
-        if (cell.was_incorrect) ctx.fillStyle = colors.incorrect;
+        const isMarkedIncorrect = cell.was_incorrect || cell.is_incorrect;
+        if (isMarkedIncorrect) ctx.fillStyle = colors.incorrect;
         if (cell.is_revealed) ctx.fillStyle = colors.revealed;
 
-        ctx.font = `${fontSize}px -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif`;
+        // Given letters are drawn bold to show they are locked
+        const weight = cell.is_given ? "bold " : "";
+        ctx.font = `${weight}${fontSize}px -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif`;
         ctx.textAlign = "center";
         ctx.textBaseline = "middle";
         ctx.fillText(text, x + cs / 2, y + letterZoneCenterY);
 
         // Incorrect triangle indicator (top-right corner, NYT-style)
-        if (cell.was_incorrect && !cell.is_revealed) {
+        if (isMarkedIncorrect && !cell.is_revealed) {
           ctx.fillStyle = colors.incorrect;
           ctx.beginPath();
           ctx.moveTo(x + cs - cellBorderWidth, y + cellBorderWidth);
@@ -178,33 +189,100 @@ export function renderGrid(
     );
   }
 
-  // Draw cell borders
+  // Draw cell borders. Shaped grids outline each square so voids stay empty.
+  const isVoid = (row: number, col: number) =>
+    row < 0 ||
+    row >= height ||
+    col < 0 ||
+    col >= width ||
+    grid[row][col].kind === "void";
+  const hasVoids = grid.some((r) => r.some((cell) => cell.kind === "void"));
   ctx.strokeStyle = colors.cellBorder;
   ctx.lineWidth = cellBorderWidth;
-  for (let row = 0; row <= height; row++) {
-    const y = borderWidth + row * cs;
-    ctx.beginPath();
-    ctx.moveTo(borderWidth, y);
-    ctx.lineTo(borderWidth + width * cs, y);
-    ctx.stroke();
+  if (hasVoids) {
+    for (let row = 0; row < height; row++) {
+      for (let col = 0; col < width; col++) {
+        if (isVoid(row, col)) continue;
+        ctx.strokeRect(borderWidth + col * cs, borderWidth + row * cs, cs, cs);
+      }
+    }
+  } else {
+    for (let row = 0; row <= height; row++) {
+      const y = borderWidth + row * cs;
+      ctx.beginPath();
+      ctx.moveTo(borderWidth, y);
+      ctx.lineTo(borderWidth + width * cs, y);
+      ctx.stroke();
+    }
+    for (let col = 0; col <= width; col++) {
+      const x = borderWidth + col * cs;
+      ctx.beginPath();
+      ctx.moveTo(x, borderWidth);
+      ctx.lineTo(x, borderWidth + height * cs);
+      ctx.stroke();
+    }
   }
-  for (let col = 0; col <= width; col++) {
-    const x = borderWidth + col * cs;
-    ctx.beginPath();
-    ctx.moveTo(x, borderWidth);
-    ctx.lineTo(x, borderWidth + height * cs);
-    ctx.stroke();
+
+  // Bars between cells in barred grids, drawn over the thin cell borders
+  ctx.strokeStyle = colors.gridBorder;
+  ctx.lineWidth = BAR_WIDTH * dpr;
+  ctx.lineCap = "square";
+  for (let row = 0; row < height; row++) {
+    for (let col = 0; col < width; col++) {
+      const { bars } = grid[row][col];
+      const x = borderWidth + col * cs;
+      const y = borderWidth + row * cs;
+      const edges: [boolean, number, number, number, number][] = [
+        [bars.top, x, y, x + cs, y],
+        [bars.right, x + cs, y, x + cs, y + cs],
+        [bars.bottom, x, y + cs, x + cs, y + cs],
+        [bars.left, x, y, x, y + cs],
+      ];
+      for (const [isBarred, x1, y1, x2, y2] of edges) {
+        if (!isBarred) continue;
+        ctx.beginPath();
+        ctx.moveTo(x1, y1);
+        ctx.lineTo(x2, y2);
+        ctx.stroke();
+      }
+    }
   }
+  ctx.lineCap = "butt";
 
-  // Outer border (thicker)
+  // Outer border (thicker). Shaped grids trace the edge of their squares.
   ctx.strokeStyle = colors.gridBorder;
   ctx.lineWidth = borderWidth;
-  ctx.strokeRect(
-    borderWidth / 2,
-    borderWidth / 2,
-    width * cs + borderWidth,
-    height * cs + borderWidth,
-  );
+  if (!hasVoids) {
+    ctx.strokeRect(
+      borderWidth / 2,
+      borderWidth / 2,
+      width * cs + borderWidth,
+      height * cs + borderWidth,
+    );
+    return;
+  }
+  ctx.lineCap = "square";
+  for (let row = 0; row < height; row++) {
+    for (let col = 0; col < width; col++) {
+      if (isVoid(row, col)) continue;
+      const x = borderWidth + col * cs;
+      const y = borderWidth + row * cs;
+      const edges: [boolean, number, number, number, number][] = [
+        [isVoid(row - 1, col), x, y, x + cs, y],
+        [isVoid(row, col + 1), x + cs, y, x + cs, y + cs],
+        [isVoid(row + 1, col), x, y + cs, x + cs, y + cs],
+        [isVoid(row, col - 1), x, y, x, y + cs],
+      ];
+      for (const [isEdge, x1, y1, x2, y2] of edges) {
+        if (!isEdge) continue;
+        ctx.beginPath();
+        ctx.moveTo(x1, y1);
+        ctx.lineTo(x2, y2);
+        ctx.stroke();
+      }
+    }
+  }
+  ctx.lineCap = "butt";
 }
 
 /**
@@ -226,7 +304,7 @@ export function hitTest(
     return null;
   }
 
-  if (puzzle.grid[row][col].kind === "black") {
+  if (puzzle.grid[row][col].kind !== "letter") {
     return null;
   }
 
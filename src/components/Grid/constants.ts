@@ -10,6 +10,9 @@ export const BORDER_WIDTH = 2;
 /** Thickness of inner cell borders in CSS pixels. */
 export const CELL_BORDER_WIDTH = 1;
 
+/** Thickness of the bars between cells in barred grids, in CSS pixels. */
+export const BAR_WIDTH = 3;
+
 /** Font sizes and padding as ratios of cell size. */
 export const NUMBER_FONT_RATIO = 0.25;
 export const LETTER_FONT_RATIO = 0.64;
@@ -26,6 +29,7 @@ export interface ColorPalette {
   cellBorder: string;
   cursorCell: string;
   wordHighlight: string;
+  shadedCell: string;
   numberText: string;
   letterText: string;
   circle: string;
@@ -42,6 +46,7 @@ export const LIGHT_COLORS: ColorPalette = {
   cellBorder: "#000000",
   cursorCell: "#FFDA00",
   wordHighlight: "#A7D8FF",
+  shadedCell: "#D0D0D0",
   numberText: "#000000",
   letterText: "#000000",
   circle: "#000000",
@@ -58,6 +63,7 @@ export const DARK_COLORS: ColorPalette = {
   cellBorder: "#333333",
   cursorCell: "#C8A200",
   wordHighlight: "#2A5080",
+  shadedCell: "#6A6A6A",
   numberText: "#C0C0C0",
   letterText: "#EDEDED",
   circle: "#C0C0C0",
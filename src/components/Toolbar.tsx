@@ -13,12 +13,15 @@ export default function Toolbar({ onOpenSettings, onGoHome }: ToolbarProps) {
   const isSolved = usePuzzleStore((s) => s.isSolved);
   const isPencilMode = usePuzzleStore((s) => s.isPencilMode);
   const isRebusMode = usePuzzleStore((s) => s.isRebusMode);
-  const { openPuzzleFile } = usePuzzleLoader();
+  const { openPuzzleFile, unlockPuzzle, error, loading } = usePuzzleLoader();
 
   const [checkOpen, setCheckOpen] = useState(false);
   const [revealOpen, setRevealOpen] = useState(false);
+  const [unlockOpen, setUnlockOpen] = useState(false);
+  const [unlockKey, setUnlockKey] = useState("");
   const checkRef = useRef<HTMLDivElement>(null);
   const revealRef = useRef<HTMLDivElement>(null);
+  const unlockRef = useRef<HTMLDivElement>(null);
 
   // Close dropdowns on outside click
   useEffect(() => {
@@ -29,12 +32,25 @@ export default function Toolbar({ onOpenSettings, onGoHome }: ToolbarProps) {
       if (revealRef.current && !revealRef.current.contains(e.target as Node)) {
         setRevealOpen(false);
       }
+      if (unlockRef.current && !unlockRef.current.contains(e.target as Node)) {
+        setUnlockOpen(false);
+      }
     };
     document.addEventListener("mousedown", handleClickOutside);
     return () => document.removeEventListener("mousedown", handleClickOutside);
   }, []);
 
   const showCheckReveal = puzzle && puzzle.has_solution && !isSolved;
+  const showUnlock = puzzle && puzzle.is_scrambled && !isSolved;
+  const isValidKey = /^[0-9]{4}$/.test(unlockKey);
+
+  const unlock = async (key?: number) => {
+    await unlockPuzzle(key);
+    if (!usePuzzleStore.getState().puzzle?.is_scrambled) {
+      setUnlockOpen(false);
+      setUnlockKey("");
+    }
+  };
 
   const btnClass =
     "rounded bg-gray-100 px-3 py-1 text-sm font-medium text-gray-700 hover:bg-gray-200 dark:bg-gray-700 dark:text-gray-300 dark:hover:bg-gray-600";
@@ -178,6 +194,60 @@ export default function Toolbar({ onOpenSettings, onGoHome }: ToolbarProps) {
           </>
         )}
 
+        {/* Unlock dropdown for scrambled .puz solutions */}
+        {showUnlock && (
+          <div ref={unlockRef} className="relative">
+            <button
+              onClick={() => setUnlockOpen(!unlockOpen)}
+              className={btnClass}
+              title="Unlock the scrambled solution"
+            >
+              Unlock
+            </button>
+            {unlockOpen && (
+              <div className={`${dropdownClass} w-56 px-3 py-2`}>
+                <form
+                  className="flex gap-2"
+                  onSubmit={(e) => {
+                    e.preventDefault();
+                    if (isValidKey) unlock(Number(unlockKey));
+                  }}
+                >
+                  <input
+                    value={unlockKey}
+                    onChange={(e) => setUnlockKey(e.target.value)}
+                    onKeyDown={(e) => e.stopPropagation()}
+                    inputMode="numeric"
+                    maxLength={4}
+                    placeholder="4-digit key"
+                    aria-label="Unlock key"
+                    className="w-full rounded border border-gray-300 bg-white px-2 py-1 text-sm text-gray-900 dark:border-gray-600 dark:bg-gray-800 dark:text-gray-100"
+                  />
+                  <button
+                    type="submit"
+                    disabled={!isValidKey || loading}
+                    className={`${btnClass} disabled:opacity-50`}
+                  >
+                    Unlock
+                  </button>
+                </form>
+                <button
+                  className={`${dropdownItemClass} mt-1 px-0 disabled:opacity-50`}
+                  disabled={loading}
+                  onClick={() => unlock()}
+                >
+                  Try all keys
+                </button>
+                {error && (
+                  <p className="mt-1 text-xs text-red-500 dark:text-red-400">
+                    {error}
+                  </p>
+                )}
+              </div>
+            )}
+          </div>
+        )}
+
         {/* Pencil toggle */}
         {puzzle && !isSolved && (
           <button
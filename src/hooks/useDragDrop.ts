@@ -2,7 +2,19 @@ import { useEffect, useState, useRef } from "react";
 import { getCurrentWebview } from "@tauri-apps/api/webview";
 import { info } from "@tauri-apps/plugin-log";
 
-const PUZZLE_EXTENSIONS = new Set(["puz", "ipuz", "jpz", "xml"]);
+const PUZZLE_EXTENSIONS = new Set([
+  "puz",
+  "ipuz",
+  "jpz",
+  "xml",
+  "txt",
+  "xd",
+  "exolve",
+  "html",
+  "htm",
+  "rawc",
+  "json",
+]);
 
 function isPuzzleFile(path: string): boolean {
   const ext = path.split(".").pop()?.toLowerCase() ?? "";
@@ -29,13 +29,42 @@ function makeCell(kind: "black" | "letter", overrides?: Partial<Cell>): Cell {
     solution: null,
     rebus_solution: null,
     player_value: null,
+    is_given: false,
     is_circled: false,
+    bars: { top: false, right: false, bottom: false, left: false },
+    is_shaded: false,
+    background_color: null,
     was_incorrect: false,
+    is_incorrect: false,
     is_revealed: false,
     ...overrides,
   };
 }
 
+function makeClue(
+  number: number,
+  text: string,
+  row: number,
+  col: number,
+  length: number,
+  overrides?: Partial<Clue>,
+): Clue {
+  return {
+    number,
+    text,
+    row,
+    col,
+    length,
+    cells: [],
+    label: null,
+    rich_text: [],
+    enumeration: null,
+    references: [],
+    continued: [],
+    ...overrides,
+  };
+}
+
 function makeTestPuzzle(): Puzzle {
   const L = (num?: number, sol?: string) =>
     makeCell("letter", { number: num ?? null, solution: sol ?? "A" });
@@ -50,16 +79,16 @@ function makeTestPuzzle(): Puzzle {
   ];
 
   const across: Clue[] = [
-    { number: 1, text: "1 across", row: 0, col: 0, length: 5 },
-    { number: 6, text: "6 across", row: 1, col: 0, length: 1 },
-    { number: 9, text: "9 across", row: 2, col: 0, length: 5 },
-    { number: 17, text: "17 across", row: 4, col: 0, length: 5 },
+    makeClue(1, "1 across", 0, 0, 5),
+    makeClue(6, "6 across", 1, 0, 1),
+    makeClue(9, "9 across", 2, 0, 5),
+    makeClue(17, "17 across", 4, 0, 5),
   ];
 
   const down: Clue[] = [
-    { number: 1, text: "1 down", row: 0, col: 0, length: 5 },
-    { number: 3, text: "3 down", row: 0, col: 2, length: 5 },
-    { number: 5, text: "5 down", row: 0, col: 4, length: 5 },
+    makeClue(1, "1 down", 0, 0, 5),
+    makeClue(3, "3 down", 0, 2, 5),
+    makeClue(5, "5 down", 0, 4, 5),
   ];
 
   return {
@@ -70,9 +99,10 @@ function makeTestPuzzle(): Puzzle {
     width: 5,
     height: 5,
     grid,
-    clues: { across, down },
+    clues: { across, down, other: [] },
     has_solution: true,
     is_scrambled: false,
+    timer: null,
   };
 }
 
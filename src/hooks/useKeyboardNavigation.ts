@@ -152,7 +152,7 @@ export function useKeyboardNavigation() {
 
           case "backspace": {
             const cell = puzzle.grid[cursor.row][cursor.col];
-            if (cell.player_value) {
+            if (cell.player_value && !cell.is_given) {
               // Clear current cell
               state.setCellValue(cursor.row, cursor.col, null);
             } else {
@@ -57,22 +57,49 @@ function makeCell(kind: "black" | "letter", overrides?: Partial<Cell>): Cell {
     solution: null,
     rebus_solution: null,
     player_value: null,
+    is_given: false,
     is_circled: false,
+    bars: { top: false, right: false, bottom: false, left: false },
+    is_shaded: false,
+    background_color: null,
     was_incorrect: false,
+    is_incorrect: false,
     is_revealed: false,
     ...overrides,
   };
 }
 
+function makeClue(
+  number: number,
+  text: string,
+  row: number,
+  col: number,
+  length: number,
+  overrides?: Partial<Clue>,
+): Clue {
+  return {
+    number,
+    text,
+    row,
+    col,
+    length,
+    cells: [],
+    label: null,
+    rich_text: [],
+    enumeration: null,
+    references: [],
+    continued: [],
+    ...overrides,
+  };
+}
+
 function makeTestPuzzle(): Puzzle {
   const L = (num?: number) =>
     makeCell("letter", { number: num ?? null, solution: "A" });
 
   const grid: Cell[][] = [[L(1)]];
-  const across: Clue[] = [
-    { number: 1, text: "Clue", row: 0, col: 0, length: 1 },
-  ];
-  const down: Clue[] = [{ number: 1, text: "Clue", row: 0, col: 0, length: 1 }];
+  const across: Clue[] = [makeClue(1, "Clue", 0, 0, 1)];
+  const down: Clue[] = [makeClue(1, "Clue", 0, 0, 1)];
 
   return {
     title: "Test",
@@ -82,9 +109,10 @@ function makeTestPuzzle(): Puzzle {
     width: 1,
     height: 1,
     grid,
-    clues: { across, down },
+    clues: { across, down, other: [] },
     has_solution: true,
     is_scrambled: false,
+    timer: null,
   };
 }
 
@@ -190,4 +218,54 @@ describe("usePuzzleLoader", () => {
 
     expect(result.current.error).toBe("string error");
   });
+
+  it("unlocks the open puzzle with a key", async () => {
+    const scrambled = makeTestPuzzle();
+    scrambled.grid[0][0].solution = "Z";
+    scrambled.has_solution = false;
+    scrambled.is_scrambled = true;
+    mockOpen.mockResolvedValue("/test/locked.puz");
+    mockInvoke.mockResolvedValueOnce(scrambled);
+    mockInvoke.mockResolvedValueOnce(makeTestPuzzle());
+    mockLoadProgress.mockResolvedValue(null);
+
+    const { result } = renderHook(() => usePuzzleLoader());
+
+    await act(async () => {
+      await result.current.openPuzzleFile();
+    });
+    await act(async () => {
+      await result.current.unlockPuzzle(1234);
+    });
+
+    expect(mockInvoke).toHaveBeenLastCalledWith("unlock_puzzle", {
+      filePath: "/test/locked.puz",
+      key: 1234,
+    });
+    const puzzle = usePuzzleStore.getState().puzzle!;
+    expect(puzzle.is_scrambled).toBe(false);
+    expect(puzzle.grid[0][0].solution).toBe("A");
+  });
+
+  it("sets error when no key unlocks the puzzle", async () => {
+    mockOpen.mockResolvedValue("/test/locked.puz");
+    mockInvoke.mockResolvedValueOnce(makeTestPuzzle());
+    mockInvoke.mockRejectedValueOnce("no key unscrambles the solution");
+    mockLoadProgress.mockResolvedValue(null);
+
+    const { result } = renderHook(() => usePuzzleLoader());
+
+    await act(async () => {
+      await result.current.openPuzzleFile();
+    });
+    await act(async () => {
+      await result.current.unlockPuzzle();
+    });
+
+    expect(mockInvoke).toHaveBeenLastCalledWith("unlock_puzzle", {
+      filePath: "/test/locked.puz",
+      key: null,
+    });
+    expect(result.current.error).toBe("no key unscrambles the solution");
+  });
 });
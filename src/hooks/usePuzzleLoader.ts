@@ -11,6 +11,9 @@ import type { Puzzle } from "../types/puzzle";
 import type { PuzzleProgress } from "../types/progress";
 import type { LibraryEntry } from "../types/library";
 
+/** Path of the puzzle open in the solver, for reloading it unlocked. */
+let openFilePath: string | null = null;
+
 async function loadAndOpenPuzzle(
   filePath: string,
   loadPuzzle: (puzzle: Puzzle) => void,
@@ -20,6 +23,7 @@ async function loadAndOpenPuzzle(
 
   const puzzle = await invoke<Puzzle>("open_puzzle", { filePath });
   loadPuzzle(puzzle);
+  openFilePath = filePath;
 
   // Check for saved progress and restore it
   const progress = await loadProgress(filePath);
@@ -64,6 +68,7 @@ async function loadAndOpenPuzzle(
 export function usePuzzleLoader() {
   const loadPuzzle = usePuzzleStore((s) => s.loadPuzzle);
   const restoreProgress = usePuzzleStore((s) => s.restoreProgress);
+  const unlockSolution = usePuzzleStore((s) => s.unlockSolution);
   const [error, setError] = useState<string | null>(null);
   const [loading, setLoading] = useState(false);
 
@@ -75,8 +80,22 @@ export function usePuzzleLoader() {
         filters: [
           {
             name: "Crossword Puzzles",
-            extensions: ["puz", "ipuz", "jpz", "xml"],
+            extensions: [
+              "puz",
+              "ipuz",
+              "jpz",
+              "xml",
+              "txt",
+              "xd",
+              "exolve",
+              "html",
+              "htm",
+              "rawc",
+              "json",
+            ],
           },
+          // The format is detected from the contents, so allow any file
+          { name: "All Files", extensions: ["*"] },
         ],
         multiple: false,
         directory: false,
@@ -112,5 +131,28 @@ export function usePuzzleLoader() {
     [loadPuzzle, restoreProgress],
   );
 
-  return { openPuzzleFile, openPuzzleByPath, error, loading };
+  /** Unlock the open scrambled puzzle with its key, or by trying every key. */
+  const unlockPuzzle = useCallback(
+    async (key?: number) => {
+      if (!openFilePath) return;
+      try {
+        setError(null);
+        setLoading(true);
+        const unlocked = await invoke<Puzzle>("unlock_puzzle", {
+          filePath: openFilePath,
+          key: key ?? null,
+        });
+        unlockSolution(unlocked);
+      } catch (err) {
+        const message = err instanceof Error ? err.message : String(err);
+        setError(message);
+        logError(`Failed to unlock puzzle: ${message}`);
+      } finally {
+        setLoading(false);
+      }
+    },
+    [unlockSolution],
+  );
+
+  return { openPuzzleFile, openPuzzleByPath, unlockPuzzle, error, loading };
 }
@@ -28,18 +28,53 @@ function loadMinimalPuzzle() {
           solution: "A",
           rebus_solution: null,
           player_value: null,
+          is_given: false,
           is_circled: false,
+          bars: { top: false, right: false, bottom: false, left: false },
+          is_shaded: false,
+          background_color: null,
           was_incorrect: false,
+          is_incorrect: false,
           is_revealed: false,
         },
       ],
     ],
     clues: {
-      across: [{ number: 1, text: "Clue", row: 0, col: 0, length: 1 }],
-      down: [{ number: 1, text: "Clue", row: 0, col: 0, length: 1 }],
+      across: [
+        {
+          number: 1,
+          text: "Clue",
+          row: 0,
+          col: 0,
+          length: 1,
+          cells: [],
+          label: null,
+          rich_text: [],
+          enumeration: null,
+          references: [],
+          continued: [],
+        },
+      ],
+      down: [
+        {
+          number: 1,
+          text: "Clue",
+          row: 0,
+          col: 0,
+          length: 1,
+          cells: [],
+          label: null,
+          rich_text: [],
+          enumeration: null,
+          references: [],
+          continued: [],
+        },
+      ],
+      other: [],
     },
     has_solution: true,
     is_scrambled: false,
+    timer: null,
   });
 }
 
@@ -24,13 +24,42 @@ function makeCell(kind: "black" | "letter", overrides?: Partial<Cell>): Cell {
     solution: null,
     rebus_solution: null,
     player_value: null,
+    is_given: false,
     is_circled: false,
+    bars: { top: false, right: false, bottom: false, left: false },
+    is_shaded: false,
+    background_color: null,
     was_incorrect: false,
+    is_incorrect: false,
     is_revealed: false,
     ...overrides,
   };
 }
 
+function makeClue(
+  number: number,
+  text: string,
+  row: number,
+  col: number,
+  length: number,
+  overrides?: Partial<Clue>,
+): Clue {
+  return {
+    number,
+    text,
+    row,
+    col,
+    length,
+    cells: [],
+    label: null,
+    rich_text: [],
+    enumeration: null,
+    references: [],
+    continued: [],
+    ...overrides,
+  };
+}
+
 /**
  * Build a 5x5 puzzle for testing:
  * L L L L L    (row 0: 1-across)
@@ -56,16 +85,16 @@ function makeTestPuzzle(): Puzzle {
   ];
 
   const across: Clue[] = [
-    { number: 1, text: "1 across", row: 0, col: 0, length: 5 },
-    { number: 6, text: "6 across", row: 1, col: 0, length: 1 },
-    { number: 9, text: "9 across", row: 2, col: 0, length: 5 },
-    { number: 17, text: "17 across", row: 4, col: 0, length: 5 },
+    makeClue(1, "1 across", 0, 0, 5),
+    makeClue(6, "6 across", 1, 0, 1),
+    makeClue(9, "9 across", 2, 0, 5),
+    makeClue(17, "17 across", 4, 0, 5),
   ];
 
   const down: Clue[] = [
-    { number: 1, text: "1 down", row: 0, col: 0, length: 5 },
-    { number: 3, text: "3 down", row: 0, col: 2, length: 5 },
-    { number: 5, text: "5 down", row: 0, col: 4, length: 5 },
+    makeClue(1, "1 down", 0, 0, 5),
+    makeClue(3, "3 down", 0, 2, 5),
+    makeClue(5, "5 down", 0, 4, 5),
   ];
 
   return {
@@ -76,9 +105,10 @@ function makeTestPuzzle(): Puzzle {
     width: 5,
     height: 5,
     grid,
-    clues: { across, down },
+    clues: { across, down, other: [] },
     has_solution: true,
     is_scrambled: false,
+    timer: null,
   };
 }
 
@@ -162,6 +192,15 @@ describe("loadPuzzle", () => {
     expect(usePuzzleStore.getState().timerRunning).toBe(true);
   });
 
+  it("continues a timer saved in the puzzle file", () => {
+    const puzzle = makeTestPuzzle();
+    puzzle.timer = { elapsed_seconds: 754, is_running: false };
+    usePuzzleStore.getState().loadPuzzle(puzzle);
+    const state = usePuzzleStore.getState();
+    expect(state.elapsedSeconds).toBe(754);
+    expect(state.timerRunning).toBe(false);
+  });
+
   it("skips black cells when finding first letter cell", () => {
     const puzzle = makeTestPuzzle();
     puzzle.grid[0][0] = makeCell("black");
@@ -226,6 +265,16 @@ describe("setCellValue", () => {
     ).toBeNull();
   });
 
+  it("ignores writes to given cells", () => {
+    const puzzle = makeTestPuzzle();
+    puzzle.grid[0][0].player_value = "A";
+    puzzle.grid[0][0].is_given = true;
+    usePuzzleStore.getState().loadPuzzle(puzzle);
+    usePuzzleStore.getState().setCellValue(0, 0, "X");
+    usePuzzleStore.getState().setCellValue(0, 0, null);
+    expect(usePuzzleStore.getState().puzzle!.grid[0][0].player_value).toBe("A");
+  });
+
   it("tracks pencil cells when pencil mode is on", () => {
     usePuzzleStore.getState().togglePencilMode();
     usePuzzleStore.getState().setCellValue(0, 0, "X");
@@ -623,6 +672,56 @@ describe("revealPuzzle", () => {
       }
     }
   });
+
+  it("does not reveal given cells", () => {
+    const puzzle = makeTestPuzzle();
+    puzzle.grid[0][0].player_value = "A";
+    puzzle.grid[0][0].is_given = true;
+    usePuzzleStore.getState().loadPuzzle(puzzle);
+    usePuzzleStore.getState().revealPuzzle();
+    const state = usePuzzleStore.getState();
+    expect(state.puzzle!.grid[0][0].is_revealed).toBe(false);
+    expect(state.puzzle!.grid[0][1].is_revealed).toBe(true);
+  });
+});
+
+// ── unlockSolution ──────────────────────────────────────────────────────
+
+describe("unlockSolution", () => {
+  function makeScrambledPuzzle(): Puzzle {
+    const puzzle = makeTestPuzzle();
+    for (const row of puzzle.grid) {
+      for (const cell of row) {
+        if (cell.kind === "letter") cell.solution = "Z";
+      }
+    }
+    puzzle.has_solution = false;
+    puzzle.is_scrambled = true;
+    return puzzle;
+  }
+
+  beforeEach(() => {
+    usePuzzleStore.getState().loadPuzzle(makeScrambledPuzzle());
+  });
+
+  it("takes the unlocked solution and keeps player entries", () => {
+    usePuzzleStore.getState().setCellValue(0, 0, "A");
+    usePuzzleStore.getState().unlockSolution(makeTestPuzzle());
+    const state = usePuzzleStore.getState();
+    expect(state.puzzle!.has_solution).toBe(true);
+    expect(state.puzzle!.is_scrambled).toBe(false);
+    expect(state.puzzle!.grid[0][1].solution).toBe("A");
+    expect(state.puzzle!.grid[0][0].player_value).toBe("A");
+  });
+
+  it("ignores a puzzle of a different size", () => {
+    const other = makeTestPuzzle();
+    other.width = 4;
+    usePuzzleStore.getState().unlockSolution(other);
+    const state = usePuzzleStore.getState();
+    expect(state.puzzle!.is_scrambled).toBe(true);
+    expect(state.puzzle!.grid[0][0].solution).toBe("Z");
+  });
 });
 
 // ── resetPuzzle ─────────────────────────────────────────────────────────
@@ -644,6 +743,15 @@ describe("resetPuzzle", () => {
     ).toBeNull();
   });
 
+  it("keeps given letters", () => {
+    const puzzle = makeTestPuzzle();
+    puzzle.grid[0][0].player_value = "A";
+    puzzle.grid[0][0].is_given = true;
+    usePuzzleStore.getState().loadPuzzle(puzzle);
+    usePuzzleStore.getState().resetPuzzle();
+    expect(usePuzzleStore.getState().puzzle!.grid[0][0].player_value).toBe("A");
+  });
+
   it("clears was_incorrect and is_revealed flags", () => {
     usePuzzleStore.getState().setCellValue(0, 0, "Z");
     usePuzzleStore.getState().checkCell(0, 0);
@@ -958,13 +1066,15 @@ describe("selectors", () => {
       grid,
       clues: {
         across: [
-          { number: 1, text: "1 across", row: 0, col: 0, length: 3 },
-          { number: 2, text: "2 across", row: 2, col: 0, length: 3 },
+          makeClue(1, "1 across", 0, 0, 3),
+          makeClue(2, "2 across", 2, 0, 3),
         ],
-        down: [{ number: 1, text: "1 down", row: 0, col: 1, length: 3 }],
+        down: [makeClue(1, "1 down", 0, 1, 3)],
+        other: [],
       },
       has_solution: true,
       is_scrambled: false,
+      timer: null,
     };
 
     usePuzzleStore.setState({ puzzle });
@@ -61,6 +61,8 @@ export interface PuzzleState {
   revealCell: (row: number, col: number) => void;
   revealWord: () => void;
   revealPuzzle: () => void;
+  /** Take the solution from an unlocked copy of a scrambled puzzle, keeping the player's entries. */
+  unlockSolution: (unlocked: Puzzle) => void;
 
   // Pencil mode
   togglePencilMode: () => void;
@@ -111,8 +113,9 @@ export const usePuzzleStore = create<PuzzleState>()(
         state.puzzle = puzzle;
         state.cursor = { row: startRow, col: startCol };
         state.direction = "across";
-        state.elapsedSeconds = 0;
-        state.timerRunning = true;
+        // Continue a timer saved in the file (e.g. by Across Lite)
+        state.elapsedSeconds = puzzle.timer?.elapsed_seconds ?? 0;
+        state.timerRunning = puzzle.timer?.is_running ?? true;
         state.isSolved = false;
         state.usedHelp = false;
         state.showIncorrectNotice = false;
@@ -147,7 +150,7 @@ export const usePuzzleStore = create<PuzzleState>()(
       set((state) => {
         if (!state.puzzle) return;
         const cell = state.puzzle.grid[row][col];
-        if (cell.kind === "black") return;
+        if (cell.kind !== "letter" || cell.is_given) return;
         cell.player_value = value;
         const key = `${row},${col}`;
         if (value === null) {
@@ -201,7 +204,7 @@ export const usePuzzleStore = create<PuzzleState>()(
       for (let r = 0; r < puzzle.height; r++) {
         for (let c = 0; c < puzzle.width; c++) {
           const cell = puzzle.grid[r][c];
-          if (cell.kind === "black") continue;
+          if (cell.kind !== "letter") continue;
           const actual = cell.player_value?.toUpperCase() ?? "";
           if (!actual) {
             allFilled = false;
@@ -263,9 +266,10 @@ export const usePuzzleStore = create<PuzzleState>()(
         for (let r = 0; r < state.puzzle.height; r++) {
           for (let c = 0; c < state.puzzle.width; c++) {
             const cell = state.puzzle.grid[r][c];
-            if (cell.kind === "letter") {
+            if (cell.kind === "letter" && !cell.is_given) {
               cell.player_value = null;
               cell.was_incorrect = false;
+              cell.is_incorrect = false;
               cell.is_revealed = false;
             }
           }
@@ -322,7 +326,9 @@ export const usePuzzleStore = create<PuzzleState>()(
         if (!state.puzzle || !state.puzzle.has_solution) return;
         state.usedHelp = true;
         const cell = state.puzzle.grid[row][col];
-        if (cell.kind === "black" || !cell.player_value) return;
+        if (cell.kind !== "letter" || cell.is_given || !cell.player_value) {
+          return;
+        }
         const expected = cell.solution?.toUpperCase() ?? "";
         const actual = cell.player_value.toUpperCase();
         if (actual !== expected) {
@@ -340,7 +346,9 @@ export const usePuzzleStore = create<PuzzleState>()(
         state.usedHelp = true;
         for (const pos of wordCells) {
           const cell = state.puzzle.grid[pos.row][pos.col];
-          if (cell.kind === "black" || !cell.player_value) continue;
+          if (cell.kind !== "letter" || cell.is_given || !cell.player_value) {
+            continue;
+          }
           const expected = cell.solution?.toUpperCase() ?? "";
           const actual = cell.player_value.toUpperCase();
           if (actual !== expected) {
@@ -357,7 +365,9 @@ export const usePuzzleStore = create<PuzzleState>()(
         for (let r = 0; r < state.puzzle.height; r++) {
           for (let c = 0; c < state.puzzle.width; c++) {
             const cell = state.puzzle.grid[r][c];
-            if (cell.kind === "black" || !cell.player_value) continue;
+            if (cell.kind !== "letter" || cell.is_given || !cell.player_value) {
+              continue;
+            }
             const expected = cell.solution?.toUpperCase() ?? "";
             const actual = cell.player_value.toUpperCase();
             if (actual !== expected) {
@@ -373,7 +383,7 @@ export const usePuzzleStore = create<PuzzleState>()(
         if (!state.puzzle || !state.puzzle.has_solution) return;
         state.usedHelp = true;
         const cell = state.puzzle.grid[row][col];
-        if (cell.kind === "black") return;
+        if (cell.kind !== "letter" || cell.is_given) return;
         cell.player_value = cell.rebus_solution ?? cell.solution;
         cell.is_revealed = true;
         cell.was_incorrect = false;
@@ -389,7 +399,7 @@ export const usePuzzleStore = create<PuzzleState>()(
         state.usedHelp = true;
         for (const pos of wordCells) {
           const cell = state.puzzle.grid[pos.row][pos.col];
-          if (cell.kind === "black") continue;
+          if (cell.kind !== "letter" || cell.is_given) continue;
           cell.player_value = cell.rebus_solution ?? cell.solution;
           cell.is_revealed = true;
           cell.was_incorrect = false;
@@ -404,7 +414,7 @@ export const usePuzzleStore = create<PuzzleState>()(
         for (let r = 0; r < state.puzzle.height; r++) {
           for (let c = 0; c < state.puzzle.width; c++) {
             const cell = state.puzzle.grid[r][c];
-            if (cell.kind === "black") continue;
+            if (cell.kind !== "letter" || cell.is_given) continue;
             cell.player_value = cell.rebus_solution ?? cell.solution;
             cell.is_revealed = true;
             cell.was_incorrect = false;
@@ -415,6 +425,29 @@ export const usePuzzleStore = create<PuzzleState>()(
       });
     },
 
+    unlockSolution: (unlocked: Puzzle) => {
+      set((state) => {
+        if (!state.puzzle) return;
+        if (
+          unlocked.width !== state.puzzle.width ||
+          unlocked.height !== state.puzzle.height
+        ) {
+          return;
+        }
+        for (let r = 0; r < state.puzzle.height; r++) {
+          for (let c = 0; c < state.puzzle.width; c++) {
+            const cell = state.puzzle.grid[r][c];
+            cell.solution = unlocked.grid[r][c].solution;
+            cell.rebus_solution = unlocked.grid[r][c].rebus_solution;
+          }
+        }
+        state.puzzle.has_solution = unlocked.has_solution;
+        state.puzzle.is_scrambled = unlocked.is_scrambled;
+      });
+      info("Puzzle unlocked");
+      get().checkSolution();
+    },
+
     // Pencil mode
     togglePencilMode: () => {
       set((state) => {
@@ -427,7 +460,7 @@ export const usePuzzleStore = create<PuzzleState>()(
       set((state) => {
         if (!state.puzzle) return;
         const cell = state.puzzle.grid[state.cursor.row][state.cursor.col];
-        if (cell.kind === "black") return;
+        if (cell.kind !== "letter" || cell.is_given) return;
         state.isRebusMode = true;
         state.rebusInput = cell.player_value ?? "";
         state.previousValue = cell.player_value;
@@ -438,7 +471,7 @@ export const usePuzzleStore = create<PuzzleState>()(
       set((state) => {
         if (!state.puzzle) return;
         const cell = state.puzzle.grid[state.cursor.row][state.cursor.col];
-        if (cell.kind !== "black") {
+        if (cell.kind === "letter") {
           cell.player_value = state.previousValue;
         }
         state.isRebusMode = false;
@@ -453,7 +486,7 @@ export const usePuzzleStore = create<PuzzleState>()(
         state.rebusInput = text;
         // Live-update cell for preview
         const cell = state.puzzle.grid[state.cursor.row][state.cursor.col];
-        if (cell.kind !== "black") {
+        if (cell.kind === "letter") {
           cell.player_value = text || null;
         }
       });
@@ -467,7 +500,7 @@ export const usePuzzleStore = create<PuzzleState>()(
       set((state) => {
         if (!state.puzzle) return;
         const cell = state.puzzle.grid[cursor.row][cursor.col];
-        if (cell.kind !== "black") {
+        if (cell.kind === "letter") {
           cell.player_value = rebusInput || null;
           // Handle pencil mode for rebus
           const key = `${cursor.row},${cursor.col}`;
@@ -525,8 +558,9 @@ export const usePuzzleStore = create<PuzzleState>()(
         for (let r = 0; r < state.puzzle.height; r++) {
           for (let c = 0; c < state.puzzle.width; c++) {
             const cellVal = progress.cellValues[idx];
-            if (cellVal !== null && state.puzzle.grid[r][c].kind === "letter") {
-              state.puzzle.grid[r][c].player_value = cellVal;
+            const cell = state.puzzle.grid[r][c];
+            if (cellVal !== null && cell.kind === "letter" && !cell.is_given) {
+              cell.player_value = cellVal;
             }
             idx++;
           }
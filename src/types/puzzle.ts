@@ -1,5 +1,13 @@
 /** Mirrors the Rust `CellKind` enum from xword-parser. */
-export type CellKind = "black" | "letter";
+export type CellKind = "black" | "letter" | "void";
+
+/** Mirrors the Rust `Bars` struct from xword-parser. */
+export interface CellBars {
+  top: boolean;
+  right: boolean;
+  bottom: boolean;
+  left: boolean;
+}
 
 /** Mirrors the Rust `Cell` struct from xword-parser. */
 export interface Cell {
@@ -8,8 +16,15 @@ export interface Cell {
   solution: string | null;
   rebus_solution: string | null;
   player_value: string | null;
+  /** A letter filled in by the puzzle's author that the player can't change. */
+  is_given: boolean;
   is_circled: boolean;
+  bars: CellBars;
+  is_shaded: boolean;
+  /** Background color as "#RRGGBB", when the puzzle gives one. */
+  background_color: string | null;
   was_incorrect: boolean;
+  is_incorrect: boolean;
   is_revealed: boolean;
 }
 
@@ -20,12 +35,54 @@ export interface Clue {
   row: number;
   col: number;
   length: number;
+  /** The answer's squares as [row, col] pairs, in answer order. */
+  cells: [number, number][];
+  /** Shown instead of the number, for clues labeled with letters. */
+  label: string | null;
+  /** Formatted runs of `text`; empty when the clue has no formatting. */
+  rich_text: TextRun[];
+  /** Word-break hint as given by the file, e.g. "(3,4)" or "5-3". */
+  enumeration: string | null;
+  /** Other clues this clue refers to. */
+  references: ClueRef[];
+  /** Further entries the answer continues into, for linked clues. */
+  continued: ClueRef[];
+}
+
+/** Mirrors the Rust `TextRun` struct from xword-parser. */
+export interface TextRun {
+  text: string;
+  italic: boolean;
+  bold: boolean;
+  subscript: boolean;
+  superscript: boolean;
+  strikethrough: boolean;
+}
+
+/** Mirrors the Rust `ClueRef` struct from xword-parser. */
+export interface ClueRef {
+  direction: string;
+  number: number;
+}
+
+/** Mirrors the Rust `ClueList` struct from xword-parser. */
+export interface ClueList {
+  direction: string;
+  label: string;
+  clues: Clue[];
 }
 
 /** Mirrors the Rust `Clues` struct from xword-parser. */
 export interface Clues {
   across: Clue[];
   down: Clue[];
+  other: ClueList[];
+}
+
+/** Mirrors the Rust `Timer` struct from xword-parser. */
+export interface PuzzleTimer {
+  elapsed_seconds: number;
+  is_running: boolean;
 }
 
 /** Mirrors the Rust `Puzzle` struct from xword-parser. */
@@ -40,6 +97,7 @@ export interface Puzzle {
   clues: Clues;
   has_solution: boolean;
   is_scrambled: boolean;
+  timer: PuzzleTimer | null;
 }
 
 export type Direction = "across" | "down";
@@ -9,8 +9,13 @@ function makeCell(kind: "black" | "letter", overrides?: Partial<Cell>): Cell {
     solution: null,
     rebus_solution: null,
     player_value: null,
+    is_given: false,
     is_circled: false,
+    bars: { top: false, right: false, bottom: false, left: false },
+    is_shaded: false,
+    background_color: null,
     was_incorrect: false,
+    is_incorrect: false,
     is_revealed: false,
     ...overrides,
   };
@@ -25,9 +30,10 @@ function makePuzzle(grid: Cell[][]): Puzzle {
     width: grid[0].length,
     height: grid.length,
     grid,
-    clues: { across: [], down: [] },
+    clues: { across: [], down: [], other: [] },
     has_solution: true,
     is_scrambled: false,
+    timer: null,
   };
 }
 
@@ -23,13 +23,42 @@ function makeCell(kind: "black" | "letter", overrides?: Partial<Cell>): Cell {
     solution: null,
     rebus_solution: null,
     player_value: null,
+    is_given: false,
     is_circled: false,
+    bars: { top: false, right: false, bottom: false, left: false },
+    is_shaded: false,
+    background_color: null,
     was_incorrect: false,
+    is_incorrect: false,
     is_revealed: false,
     ...overrides,
   };
 }
 
+function makeClue(
+  number: number,
+  text: string,
+  row: number,
+  col: number,
+  length: number,
+  overrides?: Partial<Clue>,
+): Clue {
+  return {
+    number,
+    text,
+    row,
+    col,
+    length,
+    cells: [],
+    label: null,
+    rich_text: [],
+    enumeration: null,
+    references: [],
+    continued: [],
+    ...overrides,
+  };
+}
+
 /**
  * Build a small 5x5 puzzle for testing:
  * L L L L L
@@ -56,27 +85,27 @@ function makeTestPuzzle(): Puzzle {
   ];
 
   const across: Clue[] = [
-    { number: 1, text: "1 across", row: 0, col: 0, length: 5 },
-    { number: 6, text: "6 across", row: 1, col: 0, length: 1 },
-    { number: 7, text: "7 across", row: 1, col: 2, length: 1 },
-    { number: 8, text: "8 across", row: 1, col: 4, length: 1 },
-    { number: 9, text: "9 across", row: 2, col: 0, length: 5 },
-    { number: 14, text: "14 across", row: 3, col: 0, length: 1 },
-    { number: 15, text: "15 across", row: 3, col: 2, length: 1 },
-    { number: 16, text: "16 across", row: 3, col: 4, length: 1 },
-    { number: 17, text: "17 across", row: 4, col: 0, length: 5 },
+    makeClue(1, "1 across", 0, 0, 5),
+    makeClue(6, "6 across", 1, 0, 1),
+    makeClue(7, "7 across", 1, 2, 1),
+    makeClue(8, "8 across", 1, 4, 1),
+    makeClue(9, "9 across", 2, 0, 5),
+    makeClue(14, "14 across", 3, 0, 1),
+    makeClue(15, "15 across", 3, 2, 1),
+    makeClue(16, "16 across", 3, 4, 1),
+    makeClue(17, "17 across", 4, 0, 5),
   ];
 
   const down: Clue[] = [
-    { number: 1, text: "1 down", row: 0, col: 0, length: 5 },
-    { number: 2, text: "2 down", row: 0, col: 1, length: 1 },
-    { number: 3, text: "3 down", row: 0, col: 2, length: 5 },
-    { number: 4, text: "4 down", row: 0, col: 3, length: 1 },
-    { number: 5, text: "5 down", row: 0, col: 4, length: 5 },
-    { number: 10, text: "10 down", row: 2, col: 1, length: 1 },
-    { number: 12, text: "12 down", row: 2, col: 3, length: 1 },
-    { number: 18, text: "18 down", row: 4, col: 1, length: 1 },
-    { number: 20, text: "20 down", row: 4, col: 3, length: 1 },
+    makeClue(1, "1 down", 0, 0, 5),
+    makeClue(2, "2 down", 0, 1, 1),
+    makeClue(3, "3 down", 0, 2, 5),
+    makeClue(4, "4 down", 0, 3, 1),
+    makeClue(5, "5 down", 0, 4, 5),
+    makeClue(10, "10 down", 2, 1, 1),
+    makeClue(12, "12 down", 2, 3, 1),
+    makeClue(18, "18 down", 4, 1, 1),
+    makeClue(20, "20 down", 4, 3, 1),
   ];
 
   return {
@@ -87,9 +116,10 @@ function makeTestPuzzle(): Puzzle {
     width: 5,
     height: 5,
     grid,
-    clues: { across, down },
+    clues: { across, down, other: [] },
     has_solution: true,
     is_scrambled: false,
+    timer: null,
   };
 }
 
@@ -241,13 +271,15 @@ describe("findClueAtPosition", () => {
       grid,
       clues: {
         across: [
-          { number: 1, text: "1 across", row: 0, col: 0, length: 3 },
-          { number: 2, text: "2 across", row: 2, col: 0, length: 3 },
+          makeClue(1, "1 across", 0, 0, 3),
+          makeClue(2, "2 across", 2, 0, 3),
         ],
-        down: [{ number: 1, text: "1 down", row: 0, col: 1, length: 3 }],
+        down: [makeClue(1, "1 down", 0, 1, 3)],
+        other: [],
       },
       has_solution: true,
       is_scrambled: false,
+      timer: null,
     };
 
     // Cell (1, 1) has only a down clue, no across clue
@@ -39,13 +39,42 @@ function makeCell(kind: "black" | "letter", overrides?: Partial<Cell>): Cell {
     solution: null,
     rebus_solution: null,
     player_value: null,
+    is_given: false,
     is_circled: false,
+    bars: { top: false, right: false, bottom: false, left: false },
+    is_shaded: false,
+    background_color: null,
     was_incorrect: false,
+    is_incorrect: false,
     is_revealed: false,
     ...overrides,
   };
 }
 
+function makeClue(
+  number: number,
+  text: string,
+  row: number,
+  col: number,
+  length: number,
+  overrides?: Partial<Clue>,
+): Clue {
+  return {
+    number,
+    text,
+    row,
+    col,
+    length,
+    cells: [],
+    label: null,
+    rich_text: [],
+    enumeration: null,
+    references: [],
+    continued: [],
+    ...overrides,
+  };
+}
+
 function makeTestPuzzle(): Puzzle {
   const L = (num?: number, sol?: string) =>
     makeCell("letter", { number: num ?? null, solution: sol ?? "A" });
@@ -58,13 +87,11 @@ function makeTestPuzzle(): Puzzle {
   ];
 
   const across: Clue[] = [
-    { number: 1, text: "1 across", row: 0, col: 0, length: 3 },
-    { number: 6, text: "6 across", row: 2, col: 0, length: 3 },
+    makeClue(1, "1 across", 0, 0, 3),
+    makeClue(6, "6 across", 2, 0, 3),
   ];
 
-  const down: Clue[] = [
-    { number: 1, text: "1 down", row: 0, col: 0, length: 3 },
-  ];
+  const down: Clue[] = [makeClue(1, "1 down", 0, 0, 3)];
 
   return {
     title: "Test",
@@ -74,9 +101,10 @@ function makeTestPuzzle(): Puzzle {
     width: 3,
     height: 3,
     grid,
-    clues: { across, down },
+    clues: { across, down, other: [] },
     has_solution: true,
     is_scrambled: false,
+    timer: null,
   };
 }
 
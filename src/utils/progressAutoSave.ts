@@ -21,7 +21,7 @@ function buildProgress(state: PuzzleState, filePath: string): PuzzleProgress {
   for (let r = 0; r < puzzle.height; r++) {
     for (let c = 0; c < puzzle.width; c++) {
       const cell = puzzle.grid[r][c];
-      if (cell.kind === "black") {
+      if (cell.kind !== "letter") {
         cellValues.push(null);
       } else {
         cellValues.push(cell.player_value);